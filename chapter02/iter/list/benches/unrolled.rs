@@ -0,0 +1,106 @@
+//cargo bench --bench unrolled
+//比较UnrolledList、List、Vec和std::collections::LinkedList的遍历与插入耗时
+use list::{List, UnrolledList};
+use std::collections::LinkedList;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const ELEMS: u64 = 1_000_000;
+const MIDDLE_INSERTS: u64 = 20_000;
+const ROUNDS: usize = 5;
+
+type Unrolled = UnrolledList<u64, 64>;
+
+fn time<F: FnMut()>(mut f: F) -> Duration {
+	(0..ROUNDS)
+		.map(|_| {
+			let start = Instant::now();
+			f();
+			start.elapsed()
+		})
+		.min()
+		.unwrap()
+}
+
+fn report(name: &str, count: u64, best: Duration) {
+	println!(
+		"{:<28} {:>10.2?}  {:>8.2} ns/elem",
+		name,
+		best,
+		best.as_nanos() as f64 / count as f64
+	);
+}
+
+fn iteration() {
+	let mut list = List::new();
+	let mut unrolled = Unrolled::new();
+	let mut linked = LinkedList::new();
+	for i in 0..ELEMS {
+		list.push(i);
+		unrolled.push_back(i);
+		linked.push_back(i);
+	}
+	let vec: Vec<u64> = (0..ELEMS).collect();
+
+	report("iter Vec", ELEMS, time(|| { black_box(vec.iter().sum::<u64>()); }));
+	report("iter UnrolledList<_, 64>", ELEMS, time(|| { black_box(unrolled.iter().sum::<u64>()); }));
+	report("iter List", ELEMS, time(|| { black_box(list.iter().sum::<u64>()); }));
+	report("iter LinkedList", ELEMS, time(|| { black_box(linked.iter().sum::<u64>()); }));
+}
+
+fn push_front() {
+	report("push_front UnrolledList", ELEMS, time(|| {
+		let mut unrolled = Unrolled::new();
+		for i in 0..ELEMS {
+			unrolled.push_front(i);
+		}
+		black_box(&unrolled);
+	}));
+	report("push List", ELEMS, time(|| {
+		let mut list = List::new();
+		for i in 0..ELEMS {
+			list.push(i);
+		}
+		black_box(&list);
+	}));
+	report("push_front LinkedList", ELEMS, time(|| {
+		let mut linked = LinkedList::new();
+		for i in 0..ELEMS {
+			linked.push_front(i);
+		}
+		black_box(&linked);
+	}));
+}
+
+//在中间位置插入，List只能从头部插入所以不参与比较
+fn insert_middle() {
+	report("insert middle UnrolledList", MIDDLE_INSERTS, time(|| {
+		let mut unrolled = Unrolled::new();
+		for i in 0..MIDDLE_INSERTS {
+			unrolled.insert(unrolled.len() / 2, i).unwrap();
+		}
+		black_box(&unrolled);
+	}));
+	report("insert middle Vec", MIDDLE_INSERTS, time(|| {
+		let mut vec = Vec::new();
+		for i in 0..MIDDLE_INSERTS {
+			vec.insert(vec.len() / 2, i);
+		}
+		black_box(&vec);
+	}));
+	report("insert middle LinkedList", MIDDLE_INSERTS, time(|| {
+		let mut linked = LinkedList::new();
+		for i in 0..MIDDLE_INSERTS {
+			let mut back = linked.split_off(linked.len() / 2);
+			back.push_front(i);
+			linked.append(&mut back);
+		}
+		black_box(&linked);
+	}));
+}
+
+fn main() {
+	iteration();
+	push_front();
+	insert_middle();
+}
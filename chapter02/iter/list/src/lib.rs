@@ -9,6 +9,11 @@
 //Iter => &T
 //IterMut => &mut T
 
+mod sorted;
+pub use sorted::SortedList;
+pub mod unrolled;
+pub use unrolled::UnrolledList;
+
 pub struct List<T> {
 	head: Link<T>,
 }
@@ -0,0 +1,203 @@
+//有序链表集合
+//节点结构和List相同，元素按从小到大排列且不重复。
+//集合运算按归并的方式同时遍历两个链表，直接把原有节点摘下来接到结果上，不重新分配。
+use super::{IntoIter, Iter, Link, List, Node};
+use std::iter::FromIterator;
+
+pub struct SortedList<T: Ord> {
+	head: Link<T>,
+	len: usize,
+}
+
+impl<T: Ord> Default for SortedList<T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T: Ord> SortedList<T> {
+	pub fn new() -> Self {
+		SortedList { head: None, len: 0 }
+	}
+
+	pub fn len(&self) -> usize {
+		self.len
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len == 0
+	}
+
+	//插入到合适位置，元素已存在时不插入并返回false
+	pub fn insert(&mut self, elem: T) -> bool {
+		let mut link = &mut self.head;
+		while link.as_ref().is_some_and(|node| node.elem < elem) {
+			link = &mut link.as_mut().unwrap().next;
+		}
+		if link.as_ref().is_some_and(|node| node.elem == elem) {
+			return false;
+		}
+		let node = Box::new(Node {
+			elem,
+			next: link.take(),
+		});
+		*link = Some(node);
+		self.len += 1;
+		true
+	}
+
+	pub fn remove(&mut self, elem: &T) -> Option<T> {
+		let mut link = &mut self.head;
+		while link.as_ref().is_some_and(|node| node.elem < *elem) {
+			link = &mut link.as_mut().unwrap().next;
+		}
+		if link.as_ref().is_none_or(|node| node.elem != *elem) {
+			return None;
+		}
+		let node = link.take().unwrap();
+		*link = node.next;
+		self.len -= 1;
+		Some(node.elem)
+	}
+
+	pub fn contains(&self, elem: &T) -> bool {
+		self.iter().take_while(|x| *x <= elem).any(|x| x == elem)
+	}
+
+	pub fn first(&self) -> Option<&T> {
+		self.head.as_ref().map(|node| &node.elem)
+	}
+
+	pub fn iter(&self) -> Iter<'_, T> {
+		Iter { next: self.head.as_deref() }
+	}
+
+	fn pop_node(&mut self) -> Option<Box<Node<T>>> {
+		self.head.take().map(|mut node| {
+			self.head = node.next.take();
+			self.len -= 1;
+			node
+		})
+	}
+
+	//归并两个有序链表，三个参数分别决定只在a中、只在b中、两边都有的元素是否保留
+	fn merge(mut a: Self, mut b: Self, keep_a: bool, keep_b: bool, keep_both: bool) -> Self {
+		let mut out = SortedList::new();
+		let mut tail = &mut out.head;
+		loop {
+			let node = match (a.head.as_ref(), b.head.as_ref()) {
+				(None, None) => break,
+				(Some(_), None) if !keep_a => break,
+				(None, Some(_)) if !keep_b => break,
+				(Some(x), Some(y)) if x.elem == y.elem => {
+					b.pop_node();
+					a.pop_node().filter(|_| keep_both)
+				}
+				(Some(x), Some(y)) if x.elem < y.elem => a.pop_node().filter(|_| keep_a),
+				(Some(_), None) => a.pop_node(),
+				_ => b.pop_node().filter(|_| keep_b),
+			};
+			if let Some(node) = node {
+				*tail = Some(node);
+				tail = &mut tail.as_mut().unwrap().next;
+				out.len += 1;
+			}
+		}
+		out
+	}
+
+	pub fn union(self, other: Self) -> Self {
+		Self::merge(self, other, true, true, true)
+	}
+
+	pub fn intersection(self, other: Self) -> Self {
+		Self::merge(self, other, false, false, true)
+	}
+
+	pub fn difference(self, other: Self) -> Self {
+		Self::merge(self, other, true, false, false)
+	}
+
+	pub fn symmetric_difference(self, other: Self) -> Self {
+		Self::merge(self, other, true, true, false)
+	}
+}
+
+impl<T: Ord> Drop for SortedList<T> {
+	fn drop(&mut self) {
+		let mut link = self.head.take();
+		while let Some(mut node) = link {
+			link = node.next.take();
+		}
+	}
+}
+
+impl<T: Ord> FromIterator<T> for SortedList<T> {
+	fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+		let mut list = SortedList::new();
+		for elem in iter {
+			list.insert(elem);
+		}
+		list
+	}
+}
+
+//转换成List，节点原样交给List的IntoIter
+impl<T: Ord> IntoIterator for SortedList<T> {
+	type Item = T;
+	type IntoIter = IntoIter<T>;
+	fn into_iter(mut self) -> IntoIter<T> {
+		List { head: self.head.take() }.into_iter()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::SortedList;
+
+	fn collect(list: SortedList<i32>) -> Vec<i32> {
+		list.into_iter().collect()
+	}
+
+	#[test]
+	fn basics() {
+		let mut list = SortedList::new();
+		assert!(list.is_empty());
+		assert!(list.insert(3));
+		assert!(list.insert(1));
+		assert!(list.insert(2));
+		assert!(!list.insert(2));
+		assert!(list.insert(5));
+		assert_eq!(list.len(), 4);
+		assert_eq!(list.first(), Some(&1));
+
+		assert!(list.contains(&3));
+		assert!(!list.contains(&4));
+		assert_eq!(list.remove(&4), None);
+		assert_eq!(list.remove(&1), Some(1));
+		assert_eq!(list.remove(&5), Some(5));
+		assert_eq!(list.len(), 2);
+		assert_eq!(list.iter().collect::<Vec<_>>(), vec![&2, &3]);
+	}
+
+	#[test]
+	fn set_ops() {
+		let a = || vec![1, 3, 5, 7, 9].into_iter().collect::<SortedList<_>>();
+		let b = || vec![3, 4, 5, 10].into_iter().collect::<SortedList<_>>();
+
+		let union = a().union(b());
+		assert_eq!(union.len(), 7);
+		assert_eq!(collect(union), vec![1, 3, 4, 5, 7, 9, 10]);
+
+		let intersection = a().intersection(b());
+		assert_eq!(intersection.len(), 2);
+		assert_eq!(collect(intersection), vec![3, 5]);
+
+		assert_eq!(collect(a().difference(b())), vec![1, 7, 9]);
+		assert_eq!(collect(b().difference(a())), vec![4, 10]);
+		assert_eq!(collect(a().symmetric_difference(b())), vec![1, 4, 7, 9, 10]);
+
+		assert_eq!(collect(a().union(SortedList::new())), vec![1, 3, 5, 7, 9]);
+		assert!(a().intersection(SortedList::new()).is_empty());
+	}
+}
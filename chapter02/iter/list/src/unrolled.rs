@@ -0,0 +1,549 @@
+//展开链表(unrolled linked list)
+//List每个节点只放一个元素，遍历时每一步都是一次指针跳转，缓存命中率很差。
+//这里每个节点内联存放最多N个元素，节点满了就从中间拆成两个，
+//删除后元素少于N/2时和后一个节点合并或者从后一个节点借一个元素。
+//节点之间用裸指针双向连接，这样两端都能O(1)增删，迭代器也能双向遍历。
+use std::marker::PhantomData;
+use std::mem::MaybeUninit;
+use std::ptr;
+
+//节点内的定长缓冲区，前len个元素已初始化
+struct Chunk<T, const N: usize> {
+	buf: [MaybeUninit<T>; N],
+	len: usize,
+}
+
+impl<T, const N: usize> Chunk<T, N> {
+	fn new() -> Self {
+		Chunk { buf: [const { MaybeUninit::uninit() }; N], len: 0 }
+	}
+
+	fn is_full(&self) -> bool {
+		self.len == N
+	}
+
+	fn as_slice(&self) -> &[T] {
+		unsafe { &*(ptr::slice_from_raw_parts(self.buf.as_ptr() as *const T, self.len)) }
+	}
+
+	fn as_mut_slice(&mut self) -> &mut [T] {
+		unsafe { &mut *(ptr::slice_from_raw_parts_mut(self.buf.as_mut_ptr() as *mut T, self.len)) }
+	}
+
+	fn insert(&mut self, index: usize, elem: T) {
+		debug_assert!(index <= self.len && self.len < N);
+		unsafe {
+			let p = self.buf.as_mut_ptr().add(index);
+			ptr::copy(p, p.add(1), self.len - index);
+			p.write(MaybeUninit::new(elem));
+		}
+		self.len += 1;
+	}
+
+	fn remove(&mut self, index: usize) -> T {
+		debug_assert!(index < self.len);
+		self.len -= 1;
+		unsafe {
+			let p = self.buf.as_mut_ptr().add(index);
+			let elem = p.read().assume_init();
+			ptr::copy(p.add(1), p, self.len - index);
+			elem
+		}
+	}
+
+	//把[at, len)移到other的末尾
+	fn move_tail_to(&mut self, at: usize, other: &mut Self) {
+		let count = self.len - at;
+		debug_assert!(other.len + count <= N);
+		unsafe {
+			ptr::copy_nonoverlapping(
+				self.buf.as_ptr().add(at),
+				other.buf.as_mut_ptr().add(other.len),
+				count,
+			);
+		}
+		self.len = at;
+		other.len += count;
+	}
+}
+
+impl<T, const N: usize> Drop for Chunk<T, N> {
+	fn drop(&mut self) {
+		unsafe { ptr::drop_in_place(self.as_mut_slice()) };
+	}
+}
+
+struct Node<T, const N: usize> {
+	elems: Chunk<T, N>,
+	prev: *mut Node<T, N>,
+	next: *mut Node<T, N>,
+}
+
+pub struct UnrolledList<T, const N: usize> {
+	head: *mut Node<T, N>,
+	tail: *mut Node<T, N>,
+	len: usize,
+	marker: PhantomData<Box<Node<T, N>>>,
+}
+
+unsafe impl<T: Send, const N: usize> Send for UnrolledList<T, N> {}
+unsafe impl<T: Sync, const N: usize> Sync for UnrolledList<T, N> {}
+
+impl<T, const N: usize> Default for UnrolledList<T, N> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T, const N: usize> UnrolledList<T, N> {
+	pub fn new() -> Self {
+		assert!(N >= 2, "UnrolledList needs at least 2 elements per node");
+		UnrolledList { head: ptr::null_mut(), tail: ptr::null_mut(), len: 0, marker: PhantomData }
+	}
+
+	pub fn len(&self) -> usize {
+		self.len
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len == 0
+	}
+
+	//在prev之后插入一个空节点，prev为null时插到最前面
+	fn insert_node_after(&mut self, prev: *mut Node<T, N>) -> *mut Node<T, N> {
+		let next = if prev.is_null() { self.head } else { unsafe { (*prev).next } };
+		let node = Box::into_raw(Box::new(Node { elems: Chunk::new(), prev, next }));
+		unsafe {
+			match prev.is_null() {
+				true => self.head = node,
+				false => (*prev).next = node,
+			}
+			match next.is_null() {
+				true => self.tail = node,
+				false => (*next).prev = node,
+			}
+		}
+		node
+	}
+
+	fn unlink_node(&mut self, node: *mut Node<T, N>) {
+		unsafe {
+			let node = Box::from_raw(node);
+			match node.prev.is_null() {
+				true => self.head = node.next,
+				false => (*node.prev).next = node.next,
+			}
+			match node.next.is_null() {
+				true => self.tail = node.prev,
+				false => (*node.next).prev = node.prev,
+			}
+		}
+	}
+
+	//第index个元素所在的节点和它在节点内的下标，从离得近的一端开始找
+	fn locate(&self, mut index: usize) -> (*mut Node<T, N>, usize) {
+		debug_assert!(index < self.len);
+		unsafe {
+			if index < self.len / 2 {
+				let mut node = self.head;
+				while index >= (*node).elems.len {
+					index -= (*node).elems.len;
+					node = (*node).next;
+				}
+				(node, index)
+			} else {
+				let mut node = self.tail;
+				let mut back = self.len - 1 - index;
+				while back >= (*node).elems.len {
+					back -= (*node).elems.len;
+					node = (*node).prev;
+				}
+				(node, (*node).elems.len - 1 - back)
+			}
+		}
+	}
+
+	pub fn push_front(&mut self, elem: T) {
+		if self.head.is_null() || unsafe { (*self.head).elems.is_full() } {
+			self.insert_node_after(ptr::null_mut());
+		}
+		unsafe { (*self.head).elems.insert(0, elem) };
+		self.len += 1;
+	}
+
+	pub fn push_back(&mut self, elem: T) {
+		if self.tail.is_null() || unsafe { (*self.tail).elems.is_full() } {
+			self.insert_node_after(self.tail);
+		}
+		unsafe {
+			let elems = &mut (*self.tail).elems;
+			elems.insert(elems.len, elem);
+		}
+		self.len += 1;
+	}
+
+	pub fn pop_front(&mut self) -> Option<T> {
+		if self.head.is_null() {
+			return None;
+		}
+		let head = self.head;
+		let elem = unsafe { (*head).elems.remove(0) };
+		if unsafe { (*head).elems.len } == 0 {
+			self.unlink_node(head);
+		}
+		self.len -= 1;
+		Some(elem)
+	}
+
+	pub fn pop_back(&mut self) -> Option<T> {
+		if self.tail.is_null() {
+			return None;
+		}
+		let tail = self.tail;
+		let elem = unsafe {
+			let elems = &mut (*tail).elems;
+			elems.remove(elems.len - 1)
+		};
+		if unsafe { (*tail).elems.len } == 0 {
+			self.unlink_node(tail);
+		}
+		self.len -= 1;
+		Some(elem)
+	}
+
+	pub fn get(&self, index: usize) -> Option<&T> {
+		if index >= self.len {
+			return None;
+		}
+		let (node, i) = self.locate(index);
+		unsafe { (*node).elems.as_slice().get(i) }
+	}
+
+	pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+		if index >= self.len {
+			return None;
+		}
+		let (node, i) = self.locate(index);
+		unsafe { (*node).elems.as_mut_slice().get_mut(i) }
+	}
+
+	//index等于len时插入到末尾，越界则把elem原样返回
+	pub fn insert(&mut self, index: usize, elem: T) -> Result<(), T> {
+		if index > self.len {
+			return Err(elem);
+		}
+		if index == self.len {
+			self.push_back(elem);
+			return Ok(());
+		}
+		let (mut node, mut i) = self.locate(index);
+		unsafe {
+			if (*node).elems.is_full() {
+				//从中间拆开，后一半移到新节点
+				let half = N / 2;
+				let new = self.insert_node_after(node);
+				(*node).elems.move_tail_to(half, &mut (*new).elems);
+				if i > half {
+					node = new;
+					i -= half;
+				}
+			}
+			(*node).elems.insert(i, elem);
+		}
+		self.len += 1;
+		Ok(())
+	}
+
+	pub fn remove(&mut self, index: usize) -> Option<T> {
+		if index >= self.len {
+			return None;
+		}
+		let (node, i) = self.locate(index);
+		let elem = unsafe { (*node).elems.remove(i) };
+		self.len -= 1;
+
+		unsafe {
+			let next = (*node).next;
+			if (*node).elems.len < N / 2 && !next.is_null() {
+				if (*node).elems.len + (*next).elems.len <= N {
+					//和后一个节点合并
+					(*next).elems.move_tail_to(0, &mut (*node).elems);
+					self.unlink_node(next);
+				} else {
+					//从后一个节点借一个
+					let borrowed = (*next).elems.remove(0);
+					let elems = &mut (*node).elems;
+					elems.insert(elems.len, borrowed);
+				}
+			}
+			if (*node).elems.len == 0 {
+				self.unlink_node(node);
+			}
+		}
+		Some(elem)
+	}
+
+	pub fn iter(&self) -> Iter<'_, T, N> {
+		Iter {
+			front: (self.head, 0),
+			back: (self.tail, unsafe { self.tail.as_ref() }.map_or(0, |n| n.elems.len)),
+			remaining: self.len,
+			marker: PhantomData,
+		}
+	}
+
+	pub fn iter_mut(&mut self) -> IterMut<'_, T, N> {
+		IterMut {
+			front: (self.head, 0),
+			back: (self.tail, unsafe { self.tail.as_ref() }.map_or(0, |n| n.elems.len)),
+			remaining: self.len,
+			marker: PhantomData,
+		}
+	}
+
+	//节点个数，用于观察拆分/合并
+	pub fn node_count(&self) -> usize {
+		let mut count = 0;
+		let mut node = self.head;
+		while !node.is_null() {
+			count += 1;
+			node = unsafe { (*node).next };
+		}
+		count
+	}
+}
+
+impl<T, const N: usize> Drop for UnrolledList<T, N> {
+	fn drop(&mut self) {
+		let mut node = self.head;
+		while !node.is_null() {
+			let boxed = unsafe { Box::from_raw(node) };
+			node = boxed.next;
+		}
+	}
+}
+
+impl<T, const N: usize> Extend<T> for UnrolledList<T, N> {
+	fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+		for elem in iter {
+			self.push_back(elem);
+		}
+	}
+}
+
+//IntoIter
+pub struct IntoIter<T, const N: usize>(UnrolledList<T, N>);
+
+impl<T, const N: usize> IntoIterator for UnrolledList<T, N> {
+	type Item = T;
+	type IntoIter = IntoIter<T, N>;
+	fn into_iter(self) -> IntoIter<T, N> {
+		IntoIter(self)
+	}
+}
+
+impl<T, const N: usize> Iterator for IntoIter<T, N> {
+	type Item = T;
+	fn next(&mut self) -> Option<Self::Item> {
+		self.0.pop_front()
+	}
+}
+
+impl<T, const N: usize> DoubleEndedIterator for IntoIter<T, N> {
+	fn next_back(&mut self) -> Option<T> {
+		self.0.pop_back()
+	}
+}
+
+//Iter/IterMut：front指向下一个要返回的位置，back指向上一个已返回位置（左闭右开）
+pub struct Iter<'a, T, const N: usize> {
+	front: (*mut Node<T, N>, usize),
+	back: (*mut Node<T, N>, usize),
+	remaining: usize,
+	marker: PhantomData<&'a T>,
+}
+
+pub struct IterMut<'a, T, const N: usize> {
+	front: (*mut Node<T, N>, usize),
+	back: (*mut Node<T, N>, usize),
+	remaining: usize,
+	marker: PhantomData<&'a mut T>,
+}
+
+unsafe fn step_front<T, const N: usize>(pos: &mut (*mut Node<T, N>, usize)) -> *mut T {
+	let (node, i) = *pos;
+	let elem = (*node).elems.buf.as_mut_ptr().add(i) as *mut T;
+	*pos = if i + 1 == (*node).elems.len { ((*node).next, 0) } else { (node, i + 1) };
+	elem
+}
+
+unsafe fn step_back<T, const N: usize>(pos: &mut (*mut Node<T, N>, usize)) -> *mut T {
+	let (mut node, mut i) = *pos;
+	if i == 0 {
+		node = (*node).prev;
+		i = (*node).elems.len;
+	}
+	*pos = (node, i - 1);
+	(*node).elems.buf.as_mut_ptr().add(i - 1) as *mut T
+}
+
+impl<'a, T, const N: usize> Iterator for Iter<'a, T, N> {
+	type Item = &'a T;
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.remaining == 0 {
+			return None;
+		}
+		self.remaining -= 1;
+		Some(unsafe { &*step_front(&mut self.front) })
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		(self.remaining, Some(self.remaining))
+	}
+}
+
+impl<'a, T, const N: usize> DoubleEndedIterator for Iter<'a, T, N> {
+	fn next_back(&mut self) -> Option<&'a T> {
+		if self.remaining == 0 {
+			return None;
+		}
+		self.remaining -= 1;
+		Some(unsafe { &*step_back(&mut self.back) })
+	}
+}
+
+impl<'a, T, const N: usize> ExactSizeIterator for Iter<'a, T, N> {}
+
+impl<'a, T, const N: usize> Iterator for IterMut<'a, T, N> {
+	type Item = &'a mut T;
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.remaining == 0 {
+			return None;
+		}
+		self.remaining -= 1;
+		Some(unsafe { &mut *step_front(&mut self.front) })
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		(self.remaining, Some(self.remaining))
+	}
+}
+
+impl<'a, T, const N: usize> DoubleEndedIterator for IterMut<'a, T, N> {
+	fn next_back(&mut self) -> Option<&'a mut T> {
+		if self.remaining == 0 {
+			return None;
+		}
+		self.remaining -= 1;
+		Some(unsafe { &mut *step_back(&mut self.back) })
+	}
+}
+
+impl<'a, T, const N: usize> ExactSizeIterator for IterMut<'a, T, N> {}
+
+#[cfg(test)]
+mod tests {
+	use super::UnrolledList;
+	use std::rc::Rc;
+
+	#[test]
+	fn basics() {
+		let mut list: UnrolledList<i32, 4> = UnrolledList::new();
+		assert_eq!(list.pop_front(), None);
+		assert_eq!(list.pop_back(), None);
+
+		for i in 0..10 {
+			list.push_back(i);
+		}
+		for i in 1..4 {
+			list.push_front(-i);
+		}
+		assert_eq!(list.len(), 13);
+		assert_eq!(list.get(0), Some(&-3));
+		assert_eq!(list.get(12), Some(&9));
+		assert_eq!(list.get(13), None);
+		*list.get_mut(5).unwrap() *= 10;
+
+		assert_eq!(list.pop_front(), Some(-3));
+		assert_eq!(list.pop_back(), Some(9));
+		let items: Vec<_> = list.into_iter().collect();
+		assert_eq!(items, vec![-2, -1, 0, 1, 20, 3, 4, 5, 6, 7, 8]);
+	}
+
+	#[test]
+	fn insert_remove() {
+		let mut list: UnrolledList<usize, 4> = UnrolledList::new();
+		let mut expected = Vec::new();
+		assert_eq!(list.insert(1, 0), Err(0));
+
+		//伪随机位置插入再删除，和Vec对比
+		let mut x: usize = 7;
+		for i in 0..200 {
+			x = (x * 1103515245 + 12345) % (1 << 31);
+			let at = x % (expected.len() + 1);
+			list.insert(at, i).unwrap();
+			expected.insert(at, i);
+		}
+		assert!(list.iter().eq(expected.iter()));
+		assert!(list.node_count() >= 200 / 4);
+
+		while !expected.is_empty() {
+			x = (x * 1103515245 + 12345) % (1 << 31);
+			let at = x % expected.len();
+			assert_eq!(list.remove(at), Some(expected.remove(at)));
+			assert!(list.iter().eq(expected.iter()));
+		}
+		assert_eq!(list.remove(0), None);
+		assert_eq!(list.node_count(), 0);
+	}
+
+	#[test]
+	fn split_and_merge() {
+		let mut list: UnrolledList<i32, 4> = UnrolledList::new();
+		list.extend(0..4);
+		assert_eq!(list.node_count(), 1);
+		list.insert(1, 100).unwrap();               // [0, 100, 1] [2, 3]
+		assert_eq!(list.node_count(), 2);
+		assert_eq!(list.remove(1), Some(100));      // [0, 1] [2, 3]
+		assert_eq!(list.remove(0), Some(0));        // [1, 2, 3]
+		assert_eq!(list.node_count(), 1);
+		assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![1, 2, 3]);
+	}
+
+	#[test]
+	fn double_ended() {
+		let mut list: UnrolledList<i32, 3> = UnrolledList::new();
+		list.extend(0..8);
+		assert_eq!(list.iter().rev().copied().collect::<Vec<_>>(), vec![7, 6, 5, 4, 3, 2, 1, 0]);
+
+		let mut iter = list.iter();
+		assert_eq!(iter.next(), Some(&0));
+		assert_eq!(iter.next_back(), Some(&7));
+		assert_eq!(iter.next_back(), Some(&6));
+		assert_eq!(iter.next(), Some(&1));
+		assert_eq!(iter.len(), 4);
+		assert_eq!(iter.collect::<Vec<_>>(), vec![&2, &3, &4, &5]);
+
+		for x in list.iter_mut().rev().take(2) {
+			*x *= 10;
+		}
+		let mut into_iter = list.into_iter();
+		assert_eq!(into_iter.next_back(), Some(70));
+		assert_eq!(into_iter.next_back(), Some(60));
+		assert_eq!(into_iter.next(), Some(0));
+	}
+
+	#[test]
+	fn drops_elements() {
+		let elem = Rc::new(());
+		let mut list: UnrolledList<Rc<()>, 4> = UnrolledList::new();
+		for _ in 0..10 {
+			list.push_back(elem.clone());
+		}
+		list.remove(3);
+		list.pop_front();
+		assert_eq!(Rc::strong_count(&elem), 9);
+		drop(list);
+		assert_eq!(Rc::strong_count(&elem), 1);
+	}
+}
@@ -1,5 +1,8 @@
 use std::rc::Rc;
 
+mod zipper;
+pub use zipper::PersistentZipper;
+
 pub struct List<T> {
 	head: Link<T>,
 }
@@ -11,6 +14,13 @@ struct Node<T> {
 	next: Link<T>,
 }
 
+//只复制头指针，节点共享
+impl<T> Clone for List<T> {
+	fn clone(&self) -> Self {
+		List { head: self.head.clone() }
+	}
+}
+
 impl<T> List<T> {
 	pub fn new() -> Self {
 		List { head:None }
@@ -0,0 +1,178 @@
+//持久化zipper
+//和chapter06的zipper一样由光标左右两个栈组成，不过这里的栈是不可变的List，
+//每次移动或编辑都返回一个新的zipper，只新建一个节点，其余节点和旧zipper共享。
+//元素放在Rc里，移动光标时只需要把Rc从一个栈复制到另一个栈，不要求T: Clone。
+use super::{Iter, List};
+use std::iter::Map;
+use std::rc::Rc;
+
+pub struct PersistentZipper<T> {
+	left: List<Rc<T>>,
+	right: List<Rc<T>>,
+	position: usize,
+	len: usize,
+}
+
+impl<T> Clone for PersistentZipper<T> {
+	fn clone(&self) -> Self {
+		PersistentZipper {
+			left: self.left.clone(),
+			right: self.right.clone(),
+			position: self.position,
+			len: self.len,
+		}
+	}
+}
+
+impl<T> Default for PersistentZipper<T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+type ElemIter<'a, T> = Map<Iter<'a, Rc<T>>, fn(&Rc<T>) -> &T>;
+
+impl<T> PersistentZipper<T> {
+	pub fn new() -> Self {
+		PersistentZipper { left: List::new(), right: List::new(), position: 0, len: 0 }
+	}
+
+	pub fn position(&self) -> usize {
+		self.position
+	}
+
+	pub fn len(&self) -> usize {
+		self.len
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len == 0
+	}
+
+	pub fn peek_left(&self) -> Option<&T> {
+		self.left.head().map(|elem| &**elem)
+	}
+
+	pub fn peek_right(&self) -> Option<&T> {
+		self.right.head().map(|elem| &**elem)
+	}
+
+	//在光标左边/右边插入
+	pub fn push_left(&self, elem: T) -> Self {
+		PersistentZipper {
+			left: self.left.clone().append(Rc::new(elem)),
+			right: self.right.clone(),
+			position: self.position + 1,
+			len: self.len + 1,
+		}
+	}
+
+	pub fn push_right(&self, elem: T) -> Self {
+		PersistentZipper {
+			left: self.left.clone(),
+			right: self.right.clone().append(Rc::new(elem)),
+			position: self.position,
+			len: self.len + 1,
+		}
+	}
+
+	//删除光标左边/右边的元素，没有元素时返回None
+	pub fn delete_left(&self) -> Option<Self> {
+		self.left.head()?;
+		Some(PersistentZipper {
+			left: self.left.tail(),
+			right: self.right.clone(),
+			position: self.position - 1,
+			len: self.len - 1,
+		})
+	}
+
+	pub fn delete_right(&self) -> Option<Self> {
+		self.right.head()?;
+		Some(PersistentZipper {
+			left: self.left.clone(),
+			right: self.right.tail(),
+			position: self.position,
+			len: self.len - 1,
+		})
+	}
+
+	pub fn go_left(&self) -> Option<Self> {
+		let elem = self.left.head()?;
+		Some(PersistentZipper {
+			left: self.left.tail(),
+			right: self.right.clone().append(elem.clone()),
+			position: self.position - 1,
+			len: self.len,
+		})
+	}
+
+	pub fn go_right(&self) -> Option<Self> {
+		let elem = self.right.head()?;
+		Some(PersistentZipper {
+			left: self.left.clone().append(elem.clone()),
+			right: self.right.tail(),
+			position: self.position + 1,
+			len: self.len,
+		})
+	}
+
+	//从光标向左，由近到远
+	pub fn iter_left(&self) -> ElemIter<'_, T> {
+		self.left.iter().map(|elem| &**elem)
+	}
+
+	//从光标向右，由近到远
+	pub fn iter_right(&self) -> ElemIter<'_, T> {
+		self.right.iter().map(|elem| &**elem)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::PersistentZipper;
+
+	#[test]
+	fn edit() {
+		let empty = PersistentZipper::new();
+		assert!(empty.go_left().is_none());
+		assert!(empty.delete_right().is_none());
+
+		let a = empty.push_left(1).push_left(2).push_right(4).push_right(3); // [1, 2, _, 3, 4]
+		assert_eq!(a.len(), 4);
+		assert_eq!(a.position(), 2);
+		assert_eq!(a.peek_left(), Some(&2));
+		assert_eq!(a.peek_right(), Some(&3));
+
+		let b = a.go_left().unwrap();                                        // [1, _, 2, 3, 4]
+		let c = b.delete_right().unwrap().push_right(20);                    // [1, _, 20, 3, 4]
+		assert_eq!(b.position(), 1);
+		assert_eq!(c.iter_right().collect::<Vec<_>>(), vec![&20, &3, &4]);
+
+		//旧版本都没有变化
+		assert!(empty.is_empty());
+		assert_eq!(a.iter_left().collect::<Vec<_>>(), vec![&2, &1]);
+		assert_eq!(a.iter_right().collect::<Vec<_>>(), vec![&3, &4]);
+		assert_eq!(b.iter_right().collect::<Vec<_>>(), vec![&2, &3, &4]);
+
+		let d = c.go_right().unwrap().go_right().unwrap().go_right().unwrap();
+		assert!(d.go_right().is_none());
+		assert_eq!(d.position(), 4);
+		assert_eq!(d.delete_left().unwrap().iter_left().collect::<Vec<_>>(), vec![&3, &20, &1]);
+	}
+
+	#[test]
+	fn shares_elements() {
+		use std::rc::Rc;
+
+		let elem = Rc::new(());
+		let a = PersistentZipper::new().push_left(elem.clone());
+		let b = a.go_left().unwrap().push_left(Rc::new(()));
+		//移动光标没有复制元素本身
+		assert_eq!(Rc::strong_count(&elem), 2);
+		assert!(Rc::ptr_eq(a.peek_left().unwrap(), b.peek_right().unwrap()));
+		drop(a);
+		drop(b);
+		assert_eq!(Rc::strong_count(&elem), 1);
+	}
+}
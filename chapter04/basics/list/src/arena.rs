@@ -0,0 +1,437 @@
+//基于arena的双链表
+//节点都放在一个Vec里，prev/next用u32下标代替Rc<RefCell<Node>>，没有引用计数和借用标记。
+//被删除的槽位串成空闲链表供下次插入复用。
+//对外返回的Index句柄带有代数(generation)，槽位每次被释放代数加一，旧句柄再使用时就能发现已经失效。
+use std::mem;
+
+const NIL: u32 = u32::MAX;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Index {
+	slot: u32,
+	generation: u32,
+}
+
+enum Entry<T> {
+	Occupied { elem: T, prev: u32, next: u32 },
+	Free { next_free: u32 },
+}
+
+struct Slot<T> {
+	generation: u32,
+	entry: Entry<T>,
+}
+
+pub struct ArenaList<T> {
+	slots: Vec<Slot<T>>,
+	head: u32,
+	tail: u32,
+	free: u32,
+	len: usize,
+	//新建槽位的代数。compact会丢掉末尾的槽位，之后重新建出来的槽位不能从0开始，
+	//否则compact之前发出的句柄会误中新元素
+	min_generation: u32,
+}
+
+impl<T> Default for ArenaList<T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T> ArenaList<T> {
+	pub fn new() -> Self {
+		ArenaList { slots: Vec::new(), head: NIL, tail: NIL, free: NIL, len: 0, min_generation: 0 }
+	}
+
+	pub fn with_capacity(capacity: usize) -> Self {
+		ArenaList { slots: Vec::with_capacity(capacity), ..Self::new() }
+	}
+
+	pub fn len(&self) -> usize {
+		self.len
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len == 0
+	}
+
+	//已分配的槽位数，包括空闲槽位
+	pub fn slot_count(&self) -> usize {
+		self.slots.len()
+	}
+
+	pub fn contains(&self, index: Index) -> bool {
+		self.check(index).is_some()
+	}
+
+	//句柄有效时返回槽位下标
+	fn check(&self, index: Index) -> Option<u32> {
+		match self.slots.get(index.slot as usize) {
+			Some(Slot { generation, entry: Entry::Occupied { .. } }) if *generation == index.generation => {
+				Some(index.slot)
+			}
+			_ => None,
+		}
+	}
+
+	fn index_of(&self, slot: u32) -> Option<Index> {
+		if slot == NIL {
+			return None;
+		}
+		Some(Index { slot, generation: self.slots[slot as usize].generation })
+	}
+
+	fn links(&mut self, slot: u32) -> (&mut u32, &mut u32) {
+		match &mut self.slots[slot as usize].entry {
+			Entry::Occupied { prev, next, .. } => (prev, next),
+			Entry::Free { .. } => unreachable!(),
+		}
+	}
+
+	//分配槽位并把元素链接到prev和next之间
+	fn link(&mut self, elem: T, prev: u32, next: u32) -> Index {
+		let entry = Entry::Occupied { elem, prev, next };
+		let slot = if self.free != NIL {
+			let slot = self.free;
+			let old = mem::replace(&mut self.slots[slot as usize].entry, entry);
+			if let Entry::Free { next_free } = old {
+				self.free = next_free;
+			}
+			slot
+		} else {
+			assert!(self.slots.len() < NIL as usize, "ArenaList is full");
+			self.slots.push(Slot { generation: self.min_generation, entry });
+			(self.slots.len() - 1) as u32
+		};
+
+		match prev {
+			NIL => self.head = slot,
+			_ => *self.links(prev).1 = slot,
+		}
+		match next {
+			NIL => self.tail = slot,
+			_ => *self.links(next).0 = slot,
+		}
+		self.len += 1;
+		Index { slot, generation: self.slots[slot as usize].generation }
+	}
+
+	//摘下节点，槽位代数加一后放入空闲链表
+	fn unlink(&mut self, slot: u32) -> T {
+		let record = &mut self.slots[slot as usize];
+		record.generation = record.generation.wrapping_add(1);
+		let old = mem::replace(&mut record.entry, Entry::Free { next_free: self.free });
+		self.free = slot;
+		self.len -= 1;
+
+		let (elem, prev, next) = match old {
+			Entry::Occupied { elem, prev, next } => (elem, prev, next),
+			Entry::Free { .. } => unreachable!(),
+		};
+		match prev {
+			NIL => self.head = next,
+			_ => *self.links(prev).1 = next,
+		}
+		match next {
+			NIL => self.tail = prev,
+			_ => *self.links(next).0 = prev,
+		}
+		elem
+	}
+
+	pub fn push_front(&mut self, elem: T) -> Index {
+		self.link(elem, NIL, self.head)
+	}
+
+	pub fn push_back(&mut self, elem: T) -> Index {
+		self.link(elem, self.tail, NIL)
+	}
+
+	pub fn pop_front(&mut self) -> Option<T> {
+		match self.head {
+			NIL => None,
+			head => Some(self.unlink(head)),
+		}
+	}
+
+	pub fn pop_back(&mut self) -> Option<T> {
+		match self.tail {
+			NIL => None,
+			tail => Some(self.unlink(tail)),
+		}
+	}
+
+	//在index前后插入，句柄失效时把elem原样返回
+	pub fn insert_before(&mut self, index: Index, elem: T) -> Result<Index, T> {
+		match self.check(index) {
+			Some(slot) => {
+				let prev = *self.links(slot).0;
+				Ok(self.link(elem, prev, slot))
+			}
+			None => Err(elem),
+		}
+	}
+
+	pub fn insert_after(&mut self, index: Index, elem: T) -> Result<Index, T> {
+		match self.check(index) {
+			Some(slot) => {
+				let next = *self.links(slot).1;
+				Ok(self.link(elem, slot, next))
+			}
+			None => Err(elem),
+		}
+	}
+
+	pub fn remove(&mut self, index: Index) -> Option<T> {
+		let slot = self.check(index)?;
+		Some(self.unlink(slot))
+	}
+
+	pub fn get(&self, index: Index) -> Option<&T> {
+		let slot = self.check(index)?;
+		match &self.slots[slot as usize].entry {
+			Entry::Occupied { elem, .. } => Some(elem),
+			Entry::Free { .. } => None,
+		}
+	}
+
+	pub fn get_mut(&mut self, index: Index) -> Option<&mut T> {
+		let slot = self.check(index)?;
+		match &mut self.slots[slot as usize].entry {
+			Entry::Occupied { elem, .. } => Some(elem),
+			Entry::Free { .. } => None,
+		}
+	}
+
+	pub fn front(&self) -> Option<Index> {
+		self.index_of(self.head)
+	}
+
+	pub fn back(&self) -> Option<Index> {
+		self.index_of(self.tail)
+	}
+
+	//沿链表前后移动句柄
+	pub fn next(&self, index: Index) -> Option<Index> {
+		let slot = self.check(index)?;
+		match &self.slots[slot as usize].entry {
+			Entry::Occupied { next, .. } => self.index_of(*next),
+			Entry::Free { .. } => None,
+		}
+	}
+
+	pub fn prev(&self, index: Index) -> Option<Index> {
+		let slot = self.check(index)?;
+		match &self.slots[slot as usize].entry {
+			Entry::Occupied { prev, .. } => self.index_of(*prev),
+			Entry::Free { .. } => None,
+		}
+	}
+
+	pub fn iter(&self) -> Iter<'_, T> {
+		Iter { list: self, front: self.head, back: self.tail, remaining: self.len }
+	}
+
+	//按链表顺序把节点重新排到Vec开头，去掉空闲槽位。
+	//所有旧句柄都会失效，remap对每个元素收到(旧句柄, 新句柄)。
+	pub fn compact<F: FnMut(Index, Index)>(&mut self, mut remap: F) {
+		//新句柄的代数比任何旧代数都大，旧句柄不会误中新槽位
+		let max = self.slots.iter().map(|s| s.generation).max();
+		let generation = max.unwrap_or(self.min_generation).wrapping_add(1);
+		self.min_generation = generation;
+		let mut old = mem::replace(&mut self.slots, Vec::with_capacity(self.len));
+		let mut cur = self.head;
+		while cur != NIL {
+			let record = &mut old[cur as usize];
+			let old_index = Index { slot: cur, generation: record.generation };
+			let (elem, next) = match mem::replace(&mut record.entry, Entry::Free { next_free: NIL }) {
+				Entry::Occupied { elem, next, .. } => (elem, next),
+				Entry::Free { .. } => unreachable!(),
+			};
+			let slot = self.slots.len() as u32;
+			let prev = if slot == 0 { NIL } else { slot - 1 };
+			let next_slot = if next == NIL { NIL } else { slot + 1 };
+			self.slots.push(Slot { generation, entry: Entry::Occupied { elem, prev, next: next_slot } });
+			remap(old_index, Index { slot, generation });
+			cur = next;
+		}
+		self.head = if self.len == 0 { NIL } else { 0 };
+		self.tail = if self.len == 0 { NIL } else { self.len as u32 - 1 };
+		self.free = NIL;
+	}
+}
+
+pub struct Iter<'a, T> {
+	list: &'a ArenaList<T>,
+	front: u32,
+	back: u32,
+	remaining: usize,
+}
+
+impl<'a, T> Iter<'a, T> {
+	fn entry(&self, slot: u32) -> (&'a T, u32, u32) {
+		match &self.list.slots[slot as usize].entry {
+			Entry::Occupied { elem, prev, next } => (elem, *prev, *next),
+			Entry::Free { .. } => unreachable!(),
+		}
+	}
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+	type Item = &'a T;
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.remaining == 0 {
+			return None;
+		}
+		self.remaining -= 1;
+		let (elem, _, next) = self.entry(self.front);
+		self.front = next;
+		Some(elem)
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		(self.remaining, Some(self.remaining))
+	}
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+	fn next_back(&mut self) -> Option<&'a T> {
+		if self.remaining == 0 {
+			return None;
+		}
+		self.remaining -= 1;
+		let (elem, prev, _) = self.entry(self.back);
+		self.back = prev;
+		Some(elem)
+	}
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+
+#[cfg(test)]
+mod tests {
+	use super::ArenaList;
+	use std::collections::HashMap;
+
+	#[test]
+	fn basics() {
+		let mut list = ArenaList::new();
+		assert_eq!(list.pop_front(), None);
+		assert_eq!(list.front(), None);
+
+		let b = list.push_back(2);
+		let a = list.push_front(1);
+		let c = list.push_back(3);
+		assert_eq!(list.len(), 3);
+		assert_eq!(list.front(), Some(a));
+		assert_eq!(list.back(), Some(c));
+		assert_eq!(list.next(a), Some(b));
+		assert_eq!(list.prev(a), None);
+		assert_eq!(list.get(b), Some(&2));
+		*list.get_mut(b).unwrap() = 20;
+
+		assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![1, 20, 3]);
+		assert_eq!(list.iter().rev().copied().collect::<Vec<_>>(), vec![3, 20, 1]);
+		assert_eq!(list.pop_back(), Some(3));
+		assert_eq!(list.pop_front(), Some(1));
+		assert_eq!(list.pop_front(), Some(20));
+		assert!(list.is_empty());
+	}
+
+	#[test]
+	fn insert_remove_around_handle() {
+		let mut list = ArenaList::new();
+		let mid = list.push_back(5);
+		let before = list.insert_before(mid, 4).unwrap();
+		list.insert_after(mid, 7).unwrap();
+		let six = list.insert_after(mid, 6).unwrap();
+		list.insert_before(before, 3).unwrap();
+		assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![3, 4, 5, 6, 7]);
+
+		assert_eq!(list.remove(mid), Some(5));
+		assert_eq!(list.next(before), Some(six));
+		assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![3, 4, 6, 7]);
+	}
+
+	#[test]
+	fn stale_handles() {
+		let mut list = ArenaList::new();
+		let a = list.push_back("a");
+		list.push_back("b");
+		assert_eq!(list.remove(a), Some("a"));
+
+		//槽位被复用，但旧句柄代数不同
+		let c = list.push_back("c");
+		assert_eq!(list.slot_count(), 2);
+		assert!(!list.contains(a));
+		assert_eq!(list.get(a), None);
+		assert_eq!(list.remove(a), None);
+		assert_eq!(list.insert_after(a, "x"), Err("x"));
+		assert_eq!(list.next(a), None);
+		assert_eq!(list.get(c), Some(&"c"));
+		assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec!["b", "c"]);
+	}
+
+	#[test]
+	fn stale_after_compact() {
+		let mut list = ArenaList::new();
+		let a = list.push_back("a");
+		let b = list.push_back("b");
+		list.remove(a);
+		list.compact(|_, _| {});
+		assert_eq!(list.slot_count(), 1);
+
+		//c重新建出了b原来的槽位
+		let c = list.push_back("c");
+		assert!(!list.contains(a));
+		assert!(!list.contains(b));
+		assert_eq!(list.get(b), None);
+		assert_eq!(list.get(c), Some(&"c"));
+
+		//清空后再compact，旧句柄同样失效
+		while list.pop_front().is_some() {}
+		list.compact(|_, _| {});
+		let d = list.push_back("d");
+		assert!(!list.contains(c));
+		assert_eq!(list.get(d), Some(&"d"));
+	}
+
+	#[test]
+	fn compact() {
+		let mut list = ArenaList::new();
+		let handles: Vec<_> = (0..10).map(|i| list.push_back(i)).collect();
+		for h in handles.iter().step_by(2) {
+			list.remove(*h);
+		}
+		//在最前面插入，链表顺序和槽位顺序不再一致
+		let front = list.push_front(100);
+		assert_eq!(list.slot_count(), 10);
+
+		let mut remapped = HashMap::new();
+		list.compact(|old, new| {
+			remapped.insert(old, new);
+		});
+		assert_eq!(list.slot_count(), 6);
+		assert_eq!(remapped.len(), 6);
+		assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![100, 1, 3, 5, 7, 9]);
+		assert_eq!(list.iter().rev().copied().collect::<Vec<_>>(), vec![9, 7, 5, 3, 1, 100]);
+
+		//旧句柄全部失效，新句柄指向原来的元素
+		assert!(!list.contains(front));
+		assert_eq!(list.get(remapped[&front]), Some(&100));
+		for h in handles.iter().skip(1).step_by(2) {
+			assert_eq!(list.get(*h), None);
+			assert_eq!(list.get(remapped[h]).map(|x| x % 2), Some(1));
+		}
+
+		let new_front = remapped[&front];
+		list.insert_after(new_front, 50).unwrap();
+		list.push_back(11);
+		assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![100, 50, 1, 3, 5, 7, 9, 11]);
+
+		let mut empty: ArenaList<i32> = ArenaList::new();
+		empty.compact(|_, _| unreachable!());
+		assert_eq!(empty.pop_front(), None);
+	}
+}
@@ -2,10 +2,18 @@ use std::rc::Rc;
 use std::cell::RefCell;
 use std::cell::Ref;
 use std::cell::RefMut;
+use std::error::Error;
+use std::fmt;
+
+mod sync_list;
+pub use sync_list::SyncList;
+pub mod arena;
+pub use arena::{ArenaList, Index};
 
 pub struct List<T> {
 	head: Link<T>,
 	tail: Link<T>,
+	len: usize,
 }
 
 type Link<T> = Option<Rc<RefCell<Node<T>>>>;
@@ -26,9 +34,15 @@ impl<T> Node<T> {
 	}
 }
 
+impl<T> Default for List<T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
 impl<T> List<T> {
 	pub fn new() -> Self {
-		List { head: None, tail: None }
+		List { head: None, tail: None, len: 0 }
 	}
 
 	pub fn push_front(&mut self, elem: T) {
@@ -45,6 +59,7 @@ impl<T> List<T> {
 				self.head = Some(node);
 			}
 		}
+		self.len += 1;
 	}
 
 	pub fn push_back(&mut self, elem: T) {
@@ -61,6 +76,7 @@ impl<T> List<T> {
 				self.tail = Some(node);
 			}
 		}
+		self.len += 1;
 	}
 
 	pub fn pop_front(&mut self) -> Option<T> {
@@ -74,7 +90,7 @@ impl<T> List<T> {
 					self.tail.take();
 				}
 			}
-			
+			self.len -= 1;
 			Rc::try_unwrap(node).ok().unwrap().into_inner().elem //对照手册好好理解
 		})
 	}
@@ -90,10 +106,19 @@ impl<T> List<T> {
 					self.head.take();
 				}
 			}
+			self.len -= 1;
 			Rc::try_unwrap(node).ok().unwrap().into_inner().elem
 		})
 	}
 
+	pub fn len(&self) -> usize {
+		self.len
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len == 0
+	}
+
 	//pub fn peek_front(&self) -> Option<&T> {
 	pub fn peek_front(&self) -> Option<Ref<T>> {
 		self.head.as_ref().map(|node| {
@@ -122,6 +147,364 @@ impl<T> List<T> {
 	}
 }
 
+//不会panic的借用接口
+//peek_*在节点已被借用时会panic，try_*把这种情况以及空链表都变成错误返回。
+//*_mut只需要&self，和RefCell一样在运行时检查借用规则。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListBorrowError {
+	Empty,
+	OutOfBounds,            //下标越界
+	AlreadyMutablyBorrowed, //已有RefMut存在，无法再借用
+	AlreadyBorrowed,        //已有Ref或RefMut存在，无法可变借用
+}
+
+impl fmt::Display for ListBorrowError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			ListBorrowError::Empty => write!(f, "list is empty"),
+			ListBorrowError::OutOfBounds => write!(f, "index out of bounds"),
+			ListBorrowError::AlreadyMutablyBorrowed => write!(f, "element is already mutably borrowed"),
+			ListBorrowError::AlreadyBorrowed => write!(f, "element is already borrowed"),
+		}
+	}
+}
+
+impl Error for ListBorrowError {}
+
+fn try_borrow_elem<T>(link: &Link<T>) -> Result<Ref<'_, T>, ListBorrowError> {
+	let node = link.as_ref().ok_or(ListBorrowError::Empty)?;
+	node.try_borrow()
+		.map(|node| Ref::map(node, |node| &node.elem))
+		.map_err(|_| ListBorrowError::AlreadyMutablyBorrowed)
+}
+
+fn try_borrow_elem_mut<T>(link: &Link<T>) -> Result<RefMut<'_, T>, ListBorrowError> {
+	let node = link.as_ref().ok_or(ListBorrowError::Empty)?;
+	node.try_borrow_mut()
+		.map(|node| RefMut::map(node, |node| &mut node.elem))
+		.map_err(|_| ListBorrowError::AlreadyBorrowed)
+}
+
+impl<T> List<T> {
+	pub fn try_peek_front(&self) -> Result<Ref<'_, T>, ListBorrowError> {
+		try_borrow_elem(&self.head)
+	}
+
+	pub fn try_peek_back(&self) -> Result<Ref<'_, T>, ListBorrowError> {
+		try_borrow_elem(&self.tail)
+	}
+
+	pub fn try_peek_front_mut(&self) -> Result<RefMut<'_, T>, ListBorrowError> {
+		try_borrow_elem_mut(&self.head)
+	}
+
+	pub fn try_peek_back_mut(&self) -> Result<RefMut<'_, T>, ListBorrowError> {
+		try_borrow_elem_mut(&self.tail)
+	}
+
+	//闭包形式的访问，guard在返回前就已释放
+	pub fn with_front<R, F: FnOnce(&T) -> R>(&self, f: F) -> Result<R, ListBorrowError> {
+		self.try_peek_front().map(|elem| f(&elem))
+	}
+
+	pub fn with_back<R, F: FnOnce(&T) -> R>(&self, f: F) -> Result<R, ListBorrowError> {
+		self.try_peek_back().map(|elem| f(&elem))
+	}
+
+	pub fn with_front_mut<R, F: FnOnce(&mut T) -> R>(&self, f: F) -> Result<R, ListBorrowError> {
+		self.try_peek_front_mut().map(|mut elem| f(&mut elem))
+	}
+
+	pub fn with_back_mut<R, F: FnOnce(&mut T) -> R>(&self, f: F) -> Result<R, ListBorrowError> {
+		self.try_peek_back_mut().map(|mut elem| f(&mut elem))
+	}
+
+	//投影到T的某个字段，例如 list.map_front(|p| &p.name)
+	pub fn map_front<U: ?Sized, F: FnOnce(&T) -> &U>(&self, f: F) -> Result<Ref<'_, U>, ListBorrowError> {
+		self.try_peek_front().map(|elem| Ref::map(elem, f))
+	}
+
+	pub fn map_back<U: ?Sized, F: FnOnce(&T) -> &U>(&self, f: F) -> Result<Ref<'_, U>, ListBorrowError> {
+		self.try_peek_back().map(|elem| Ref::map(elem, f))
+	}
+
+	pub fn map_front_mut<U: ?Sized, F: FnOnce(&mut T) -> &mut U>(&self, f: F) -> Result<RefMut<'_, U>, ListBorrowError> {
+		self.try_peek_front_mut().map(|elem| RefMut::map(elem, f))
+	}
+
+	pub fn map_back_mut<U: ?Sized, F: FnOnce(&mut T) -> &mut U>(&self, f: F) -> Result<RefMut<'_, U>, ListBorrowError> {
+		self.try_peek_back_mut().map(|elem| RefMut::map(elem, f))
+	}
+}
+
+//按下标访问
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IndexOutOfBounds {
+	pub index: usize,
+	pub len: usize,
+}
+
+impl fmt::Display for IndexOutOfBounds {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "index {} out of bounds for list of length {}", self.index, self.len)
+	}
+}
+
+impl Error for IndexOutOfBounds {}
+
+impl<T> List<T> {
+	//第index个节点，从离index较近的一端开始遍历
+	//途经的节点要try_borrow才能读到next/prev，某个节点正被RefMut借用时返回错误
+	fn node_at(&self, index: usize) -> Result<Rc<RefCell<Node<T>>>, ListBorrowError> {
+		if index >= self.len {
+			return Err(ListBorrowError::OutOfBounds);
+		}
+		let step = |node: &Rc<RefCell<Node<T>>>, forward: bool| {
+			let node = node.try_borrow().map_err(|_| ListBorrowError::AlreadyMutablyBorrowed)?;
+			let next = if forward { &node.next } else { &node.prev };
+			Ok(next.clone().unwrap())
+		};
+		if index < self.len / 2 {
+			let mut node = self.head.clone().unwrap();
+			for _ in 0..index {
+				node = step(&node, true)?;
+			}
+			Ok(node)
+		} else {
+			let mut node = self.tail.clone().unwrap();
+			for _ in index..self.len - 1 {
+				node = step(&node, false)?;
+			}
+			Ok(node)
+		}
+	}
+
+	//链表自己持有每个节点的强引用，而next/prev只能通过&mut self修改，
+	//所以在&self期间节点一定还活着，可以把它的&RefCell交出去。
+	//这里只得到RefCell的共享引用，元素的借用仍然由RefCell检查。
+	fn node_ref(&self, index: usize) -> Result<&RefCell<Node<T>>, ListBorrowError> {
+		let node = self.node_at(index)?;
+		Ok(unsafe { &*Rc::as_ptr(&node) })
+	}
+
+	pub fn get(&self, index: usize) -> Result<Ref<'_, T>, ListBorrowError> {
+		self.node_ref(index)?
+			.try_borrow()
+			.map(|node| Ref::map(node, |node| &node.elem))
+			.map_err(|_| ListBorrowError::AlreadyMutablyBorrowed)
+	}
+
+	pub fn get_mut(&self, index: usize) -> Result<RefMut<'_, T>, ListBorrowError> {
+		self.node_ref(index)?
+			.try_borrow_mut()
+			.map(|node| RefMut::map(node, |node| &mut node.elem))
+			.map_err(|_| ListBorrowError::AlreadyBorrowed)
+	}
+
+	//按下标的闭包访问，和with_front一样不会panic
+	pub fn with_at<R, F: FnOnce(&T) -> R>(&self, index: usize, f: F) -> Result<R, ListBorrowError> {
+		self.get(index).map(|elem| f(&elem))
+	}
+
+	pub fn with_at_mut<R, F: FnOnce(&mut T) -> R>(&self, index: usize, f: F) -> Result<R, ListBorrowError> {
+		self.get_mut(index).map(|mut elem| f(&mut elem))
+	}
+
+	//index等于len时插入到末尾，越界则把elem原样返回
+	pub fn insert(&mut self, index: usize, elem: T) -> Result<(), T> {
+		if index > self.len {
+			return Err(elem);
+		}
+		if index == 0 {
+			self.push_front(elem);
+		} else if index == self.len {
+			self.push_back(elem);
+		} else {
+			let next = self.node_at(index).unwrap();
+			let prev = next.borrow_mut().prev.take().unwrap();
+			let node = Node::new(elem);
+			node.borrow_mut().prev = Some(prev.clone());
+			node.borrow_mut().next = Some(next.clone());
+			prev.borrow_mut().next = Some(node.clone());
+			next.borrow_mut().prev = Some(node);
+			self.len += 1;
+		}
+		Ok(())
+	}
+
+	pub fn remove(&mut self, index: usize) -> Option<T> {
+		if index >= self.len {
+			return None;
+		}
+		if index == 0 {
+			return self.pop_front();
+		}
+		if index == self.len - 1 {
+			return self.pop_back();
+		}
+		let node = self.node_at(index).unwrap();
+		let prev = node.borrow_mut().prev.take().unwrap();
+		let next = node.borrow_mut().next.take().unwrap();
+		prev.borrow_mut().next = Some(next.clone());
+		next.borrow_mut().prev = Some(prev);
+		self.len -= 1;
+		Some(Rc::try_unwrap(node).ok().unwrap().into_inner().elem)
+	}
+
+	pub fn swap(&mut self, i: usize, j: usize) -> Result<(), IndexOutOfBounds> {
+		let len = self.len;
+		let a = self.node_at(i).map_err(|_| IndexOutOfBounds { index: i, len })?;
+		let b = self.node_at(j).map_err(|_| IndexOutOfBounds { index: j, len })?;
+		if i != j {
+			std::mem::swap(&mut a.borrow_mut().elem, &mut b.borrow_mut().elem);
+		}
+		Ok(())
+	}
+}
+
+//两个链表之间的拼接与拆分，只修改首尾指针，不重新分配节点
+impl<T> List<T> {
+	//把other的所有元素接到self末尾，other变为空
+	pub fn append(&mut self, other: &mut List<T>) {
+		match self.tail.take() {
+			Some(tail) => match other.head.take() {
+				Some(other_head) => {
+					other_head.borrow_mut().prev = Some(tail.clone());
+					tail.borrow_mut().next = Some(other_head);
+					self.tail = other.tail.take();
+					self.len += std::mem::replace(&mut other.len, 0);
+				}
+				None => {
+					self.tail = Some(tail);
+				}
+			},
+			None => std::mem::swap(self, other),
+		}
+	}
+
+	//把other的所有元素接到self开头，other变为空
+	pub fn prepend(&mut self, other: &mut List<T>) {
+		other.append(self);
+		std::mem::swap(self, other);
+	}
+
+	//在at处拆开，self保留[0, at)，返回[at, len)
+	pub fn split_off(&mut self, at: usize) -> Result<List<T>, IndexOutOfBounds> {
+		if at > self.len {
+			return Err(IndexOutOfBounds { index: at, len: self.len });
+		}
+		if at == 0 {
+			return Ok(std::mem::take(self));
+		}
+		if at == self.len {
+			return Ok(List::new());
+		}
+		let node = self.node_at(at).unwrap();
+		let prev = node.borrow_mut().prev.take().unwrap();
+		prev.borrow_mut().next.take();
+		let back = List {
+			head: Some(node),
+			tail: self.tail.replace(prev),
+			len: self.len - at,
+		};
+		self.len = at;
+		Ok(back)
+	}
+
+	//把other整个插入到index处，other变为空
+	pub fn splice_at(&mut self, index: usize, other: &mut List<T>) -> Result<(), IndexOutOfBounds> {
+		let mut back = self.split_off(index)?;
+		self.append(other);
+		self.append(&mut back);
+		Ok(())
+	}
+}
+
+//旋转，只重新连接节点，不分配
+impl<T> List<T> {
+	//前n个元素依次移到末尾
+	pub fn rotate_left(&mut self, n: usize) {
+		if self.len == 0 || n.is_multiple_of(self.len) {
+			return;
+		}
+		let new_head = self.node_at(n % self.len).unwrap();
+		let new_tail = new_head.borrow_mut().prev.take().unwrap();
+		new_tail.borrow_mut().next.take();
+
+		//先首尾相连成环，再从new_tail和new_head之间断开
+		let old_head = self.head.take().unwrap();
+		let old_tail = self.tail.take().unwrap();
+		old_head.borrow_mut().prev = Some(old_tail.clone());
+		old_tail.borrow_mut().next = Some(old_head);
+
+		self.head = Some(new_head);
+		self.tail = Some(new_tail);
+	}
+
+	//后n个元素依次移到开头
+	pub fn rotate_right(&mut self, n: usize) {
+		if self.len == 0 {
+			return;
+		}
+		self.rotate_left(self.len - n % self.len);
+	}
+}
+
+//轮转调度：每次取出队首元素并把它移到队尾
+pub struct RoundRobin<T> {
+	list: List<T>,
+}
+
+impl<T> Default for RoundRobin<T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T> From<List<T>> for RoundRobin<T> {
+	fn from(list: List<T>) -> Self {
+		RoundRobin { list }
+	}
+}
+
+impl<T> RoundRobin<T> {
+	pub fn new() -> Self {
+		RoundRobin { list: List::new() }
+	}
+
+	//新加入的元素排在下一轮的最后
+	pub fn push(&mut self, elem: T) {
+		self.list.push_back(elem);
+	}
+
+	//轮到的元素，返回时它已经在队尾了
+	pub fn advance(&mut self) -> Option<RefMut<'_, T>> {
+		self.list.rotate_left(1);
+		self.list.peek_back_mut()
+	}
+
+	//删除上一次advance返回的元素
+	pub fn remove_current(&mut self) -> Option<T> {
+		self.list.pop_back()
+	}
+
+	pub fn peek_next(&self) -> Option<Ref<'_, T>> {
+		self.list.peek_front()
+	}
+
+	pub fn len(&self) -> usize {
+		self.list.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.list.is_empty()
+	}
+
+	pub fn into_inner(self) -> List<T> {
+		self.list
+	}
+}
+
 //实现迭代器
 //Iter 不实现
 //IterMut 不实现
@@ -149,6 +532,9 @@ impl<T> DoubleEndedIterator for IntoIter<T> {
 #[cfg(test)]
 mod tests {
 	use super::List;
+	use super::RoundRobin;
+	use super::ListBorrowError;
+	use super::IndexOutOfBounds;
 
     #[test]
     fn basics() {
@@ -197,6 +583,194 @@ mod tests {
 		assert_eq!(*list.peek_back().unwrap(), 1);
 		assert_eq!(*list.peek_back_mut().unwrap(), 1);
 	}
+
+	#[test]
+	fn try_peek() {
+		let mut list = List::new();
+		assert_eq!(list.try_peek_front().err(), Some(ListBorrowError::Empty));
+		assert_eq!(list.with_back_mut(|x: &mut i32| *x).err(), Some(ListBorrowError::Empty));
+
+		list.push_back(1);
+		list.push_back(2);
+
+		{
+			let front = list.try_peek_front().unwrap();
+			assert_eq!(*front, 1);
+			assert_eq!(*list.try_peek_front().unwrap(), 1);
+			assert_eq!(list.try_peek_front_mut().err(), Some(ListBorrowError::AlreadyBorrowed));
+			*list.try_peek_back_mut().unwrap() = 20;
+		}
+
+		{
+			let _back = list.try_peek_back_mut().unwrap();
+			assert_eq!(list.try_peek_back().err(), Some(ListBorrowError::AlreadyMutablyBorrowed));
+			assert_eq!(list.with_back(|x| *x).err(), Some(ListBorrowError::AlreadyMutablyBorrowed));
+		}
+
+		assert_eq!(list.with_front_mut(|x| { *x += 10; *x }), Ok(11));
+		assert_eq!(list.with_back(|x| *x), Ok(20));
+		assert_eq!(list.try_peek_front_mut().map(|x| *x), Ok(11));
+	}
+
+	#[test]
+	fn map_field() {
+		let mut list = List::new();
+		list.push_back((1, String::from("one")));
+		list.push_back((2, String::from("two")));
+
+		assert_eq!(&*list.map_front(|pair| &pair.1).unwrap(), "one");
+		assert_eq!(&*list.map_back(|pair| pair.1.as_str()).unwrap(), "two");
+
+		list.map_back_mut(|pair| &mut pair.0).map(|mut n| *n = 22).unwrap();
+		assert_eq!(*list.map_back(|pair| &pair.0).unwrap(), 22);
+
+		let _name = list.map_front(|pair| &pair.1).unwrap();
+		assert!(list.map_front_mut(|pair| &mut pair.0).is_err());
+	}
+
+	#[test]
+	fn index() {
+		let mut list = List::new();
+		assert_eq!(list.get(0).err(), Some(ListBorrowError::OutOfBounds));
+		assert_eq!(list.remove(0), None);
+		assert_eq!(list.insert(1, 10), Err(10));
+
+		for i in 0..6 {
+			assert_eq!(list.insert(i, i), Ok(()));
+		}
+		assert_eq!(list.len(), 6);
+		assert_eq!(*list.get(1).unwrap(), 1);
+		assert_eq!(*list.get(4).unwrap(), 4);
+		assert_eq!(list.get(6).err(), Some(ListBorrowError::OutOfBounds));
+		assert_eq!(list.get_mut(6).err(), Some(ListBorrowError::OutOfBounds));
+
+		*list.get_mut(4).unwrap() = 40;
+		assert_eq!(list.insert(2, 100), Ok(()));  // [0, 1, 100, 2, 3, 40, 5]
+		assert_eq!(list.insert(5, 200), Ok(()));  // [0, 1, 100, 2, 3, 200, 40, 5]
+		assert_eq!(list.remove(3), Some(2));      // [0, 1, 100, 3, 200, 40, 5]
+		assert_eq!(list.remove(5), Some(40));     // [0, 1, 100, 3, 200, 5]
+		assert_eq!(list.remove(6), None);
+
+		assert_eq!(list.swap(0, 5), Ok(()));      // [5, 1, 100, 3, 200, 0]
+		assert_eq!(list.swap(2, 2), Ok(()));
+		assert_eq!(list.swap(1, 9), Err(IndexOutOfBounds { index: 9, len: 6 }));
+
+		assert_eq!(list.len(), 6);
+		let items: Vec<_> = list.into_iter().collect();
+		assert_eq!(items, vec![5, 1, 100, 3, 200, 0]);
+	}
+
+	#[test]
+	fn index_borrowed_node() {
+		let mut list = List::new();
+		for i in 0..5 {
+			list.push_back(i);
+		}
+		let front = list.try_peek_front_mut().unwrap();
+		//要经过front才能走到下标1
+		assert_eq!(list.get(1).err(), Some(ListBorrowError::AlreadyMutablyBorrowed));
+		assert_eq!(*list.get(3).unwrap(), 3);
+		drop(front);
+
+		//get返回的guard可以同时存在多个
+		let second = list.get(2).unwrap();
+		let third = list.get(3).unwrap();
+		assert_eq!(*second + *third, 5);
+		assert_eq!(list.get_mut(2).err(), Some(ListBorrowError::AlreadyBorrowed));
+		drop((second, third));
+
+		let mut fourth = list.get_mut(4).unwrap();
+		*fourth += 1;
+		assert_eq!(list.get(4).err(), Some(ListBorrowError::AlreadyMutablyBorrowed));
+		assert_eq!(list.with_at_mut(4, |x| *x += 1), Err(ListBorrowError::AlreadyBorrowed));
+		drop(fourth);
+		assert_eq!(list.with_at_mut(1, |x| *x += 1), Ok(()));
+		assert_eq!(list.with_at(1, |x| *x), Ok(2));
+		assert_eq!(list.with_at(4, |x| *x), Ok(5));
+	}
+
+	#[test]
+	fn append_split() {
+		let mut a = List::new();
+		let mut b = List::new();
+		for i in 0..3 {
+			a.push_back(i);
+			b.push_back(i + 10);
+		}
+
+		a.append(&mut b);                          // [0, 1, 2, 10, 11, 12]
+		assert!(b.is_empty());
+		assert_eq!(a.len(), 6);
+		b.append(&mut a);
+		assert!(a.is_empty());
+		a.append(&mut b);
+		assert_eq!(*a.peek_back().unwrap(), 12);
+
+		b.push_back(-1);
+		a.prepend(&mut b);                         // [-1, 0, 1, 2, 10, 11, 12]
+		assert!(b.is_empty());
+		assert_eq!(*a.peek_front().unwrap(), -1);
+
+		let mut back = a.split_off(4).unwrap();    // [-1, 0, 1, 2] [10, 11, 12]
+		assert_eq!(a.len(), 4);
+		assert_eq!(back.len(), 3);
+		assert_eq!(*a.peek_back().unwrap(), 2);
+		assert_eq!(*back.peek_front().unwrap(), 10);
+		assert_eq!(a.split_off(5).err(), Some(IndexOutOfBounds { index: 5, len: 4 }));
+		assert!(a.split_off(4).unwrap().is_empty());
+
+		a.splice_at(1, &mut back).unwrap();        // [-1, 10, 11, 12, 0, 1, 2]
+		assert!(back.is_empty());
+		let mut rest = a.split_off(0).unwrap();
+		assert!(a.is_empty());
+		assert_eq!(rest.pop_back(), Some(2));
+		let items: Vec<_> = rest.into_iter().collect();
+		assert_eq!(items, vec![-1, 10, 11, 12, 0, 1]);
+	}
+
+	#[test]
+	fn rotate() {
+		let mut list = List::new();
+		list.rotate_left(3);
+		list.rotate_right(3);
+		for i in 0..5 {
+			list.push_back(i);
+		}
+
+		list.rotate_left(2);                       // [2, 3, 4, 0, 1]
+		assert_eq!(*list.peek_front().unwrap(), 2);
+		assert_eq!(*list.peek_back().unwrap(), 1);
+		list.rotate_right(1);                      // [1, 2, 3, 4, 0]
+		list.rotate_left(10);
+		list.rotate_right(7);                      // [4, 0, 1, 2, 3]
+
+		assert_eq!(list.len(), 5);
+		let items: Vec<_> = list.into_iter().collect();
+		assert_eq!(items, vec![4, 0, 1, 2, 3]);
+	}
+
+	#[test]
+	fn round_robin() {
+		let mut rr = RoundRobin::new();
+		assert!(rr.advance().is_none());
+		rr.push(("a", 2));
+		rr.push(("b", 1));
+		rr.push(("c", 3));
+
+		let mut order = Vec::new();
+		while !rr.is_empty() {
+			let done = rr.advance().map(|mut job| {
+				order.push(job.0);
+				job.1 -= 1;
+				job.1 == 0
+			}).unwrap();
+			if done {
+				rr.remove_current();
+			}
+		}
+		assert_eq!(order, vec!["a", "b", "c", "a", "c", "c"]);
+		assert!(rr.is_empty());
+	}
 	
 	#[test]
 	fn into_iter() {
@@ -0,0 +1,372 @@
+//线程安全的双链表
+//每个节点是Arc<Mutex<Node>>，首尾各有一个哨兵节点。
+//加锁顺序：任何时候都只在持有某节点锁的情况下去锁它的next节点（从前往后），
+//需要从尾部开始的操作先单独读出prev，再按从前往后的顺序加锁并校验，
+//校验失败就全部释放后重试，这样等待关系不会成环，不会死锁。
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, Weak};
+
+pub struct SyncList<T> {
+	head: Arc<Mutex<Node<T>>>,
+	tail: Arc<Mutex<Node<T>>>,
+	len: AtomicUsize,
+}
+
+type Link<T> = Option<Arc<Mutex<Node<T>>>>;
+
+struct Node<T> {
+	elem: Option<T>, //哨兵节点为None，节点被摘除后也会被take
+	next: Link<T>,
+	prev: Weak<Mutex<Node<T>>>,
+}
+
+impl<T> Node<T> {
+	fn sentinel() -> Arc<Mutex<Self>> {
+		Arc::new(Mutex::new(Node {
+			elem: None,
+			next: None,
+			prev: Weak::new(),
+		}))
+	}
+}
+
+//持有Arc的锁守卫，交替加锁遍历时用来替代借用局部变量的MutexGuard
+struct Locked<'a, T> {
+	guard: MutexGuard<'a, Node<T>>, //必须声明在node之前，保证先于node析构
+	node: Arc<Mutex<Node<T>>>,
+}
+
+impl<'a, T> Locked<'a, T> {
+	fn new(node: Arc<Mutex<Node<T>>>) -> Self {
+		//Mutex在堆上，只要node这个Arc还在，它的地址就不变且一直有效
+		let guard = unsafe { &*Arc::as_ptr(&node) }.lock().unwrap();
+		Locked { guard, node }
+	}
+}
+
+impl<'a, T> Deref for Locked<'a, T> {
+	type Target = Node<T>;
+	fn deref(&self) -> &Node<T> {
+		&self.guard
+	}
+}
+
+impl<'a, T> DerefMut for Locked<'a, T> {
+	fn deref_mut(&mut self) -> &mut Node<T> {
+		&mut self.guard
+	}
+}
+
+//node.next指向的节点
+fn next_of<T>(node: &Node<T>) -> Arc<Mutex<Node<T>>> {
+	node.next.clone().expect("non-tail node in list must have next")
+}
+
+//把node从prev和next之间摘下来，三者都已加锁
+fn unlink<T>(prev: &mut Locked<T>, node: &mut Locked<T>, next: &mut Locked<T>) -> T {
+	next.prev = Arc::downgrade(&prev.node);
+	prev.next = Some(next.node.clone());
+	node.next = None;
+	node.prev = Weak::new();
+	node.elem.take().unwrap()
+}
+
+impl<T> Default for SyncList<T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T> SyncList<T> {
+	pub fn new() -> Self {
+		let head = Node::sentinel();
+		let tail = Node::sentinel();
+		head.lock().unwrap().next = Some(tail.clone());
+		tail.lock().unwrap().prev = Arc::downgrade(&head);
+		SyncList { head, tail, len: AtomicUsize::new(0) }
+	}
+
+	pub fn len(&self) -> usize {
+		self.len.load(Ordering::SeqCst)
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	//在持有tail锁时读取最后一个节点，此时它不可能被摘除
+	fn last(&self) -> Arc<Mutex<Node<T>>> {
+		self.tail.lock().unwrap().prev.upgrade().unwrap()
+	}
+
+	pub fn push_front(&self, elem: T) {
+		let mut head = Locked::new(self.head.clone());
+		let mut first = Locked::new(next_of(&head));
+		let node = Arc::new(Mutex::new(Node {
+			elem: Some(elem),
+			next: Some(first.node.clone()),
+			prev: Arc::downgrade(&self.head),
+		}));
+		first.prev = Arc::downgrade(&node);
+		head.next = Some(node);
+		self.len.fetch_add(1, Ordering::SeqCst);
+	}
+
+	pub fn push_back(&self, elem: T) {
+		let mut elem = Some(elem);
+		loop {
+			let mut last = Locked::new(self.last());
+			//加锁前尾部被修改了，或者last已被其他线程摘除(next为空)，重试
+			if !last.next.as_ref().is_some_and(|next| Arc::ptr_eq(next, &self.tail)) {
+				continue;
+			}
+			let mut tail = Locked::new(self.tail.clone());
+			let node = Arc::new(Mutex::new(Node {
+				elem: elem.take(),
+				next: Some(self.tail.clone()),
+				prev: Arc::downgrade(&last.node),
+			}));
+			tail.prev = Arc::downgrade(&node);
+			last.next = Some(node);
+			self.len.fetch_add(1, Ordering::SeqCst);
+			return;
+		}
+	}
+
+	pub fn pop_front(&self) -> Option<T> {
+		let mut head = Locked::new(self.head.clone());
+		let first = next_of(&head);
+		if Arc::ptr_eq(&first, &self.tail) {
+			return None;
+		}
+		let mut first = Locked::new(first);
+		let mut second = Locked::new(next_of(&first));
+		let elem = unlink(&mut head, &mut first, &mut second);
+		self.len.fetch_sub(1, Ordering::SeqCst);
+		Some(elem)
+	}
+
+	pub fn pop_back(&self) -> Option<T> {
+		loop {
+			let last = self.last();
+			if Arc::ptr_eq(&last, &self.head) {
+				return None;
+			}
+			//last可能已被其他线程摘除，此时prev为空
+			let prev = match last.lock().unwrap().prev.upgrade() {
+				Some(prev) => prev,
+				None => continue,
+			};
+			let mut prev = Locked::new(prev);
+			if !prev.next.as_ref().is_some_and(|next| Arc::ptr_eq(next, &last)) {
+				continue;
+			}
+			let mut last = Locked::new(last);
+			if !Arc::ptr_eq(last.next.as_ref().unwrap(), &self.tail) {
+				continue;
+			}
+			let mut tail = Locked::new(self.tail.clone());
+			let elem = unlink(&mut prev, &mut last, &mut tail);
+			self.len.fetch_sub(1, Ordering::SeqCst);
+			return Some(elem);
+		}
+	}
+
+	//删除所有满足条件的元素，返回删除的个数
+	pub fn remove_if<F>(&self, mut f: F) -> usize
+	where
+		F: FnMut(&T) -> bool,
+	{
+		let mut removed = 0;
+		let mut cur = Locked::new(self.head.clone());
+		loop {
+			let next = next_of(&cur);
+			if Arc::ptr_eq(&next, &self.tail) {
+				break;
+			}
+			let mut next = Locked::new(next);
+			if f(next.elem.as_ref().unwrap()) {
+				let mut after = Locked::new(next_of(&next));
+				unlink(&mut cur, &mut next, &mut after);
+				self.len.fetch_sub(1, Ordering::SeqCst);
+				removed += 1;
+			} else {
+				cur = next;
+			}
+		}
+		removed
+	}
+
+	pub fn contains(&self, elem: &T) -> bool
+	where
+		T: PartialEq,
+	{
+		let mut cur = Locked::new(self.head.clone());
+		loop {
+			let next = next_of(&cur);
+			if Arc::ptr_eq(&next, &self.tail) {
+				return false;
+			}
+			let next = Locked::new(next);
+			if next.elem.as_ref() == Some(elem) {
+				return true;
+			}
+			cur = next;
+		}
+	}
+}
+
+impl<T> Drop for SyncList<T> {
+	fn drop(&mut self) {
+		//逐个断开next，避免长链表递归析构导致栈溢出
+		let mut link = self.head.lock().unwrap().next.take();
+		while let Some(node) = link {
+			link = node.lock().unwrap().next.take();
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::SyncList;
+	use std::sync::Arc;
+	use std::thread;
+
+	#[test]
+	fn basics() {
+		let list = SyncList::new();
+		assert_eq!(list.pop_front(), None);
+		assert_eq!(list.pop_back(), None);
+
+		list.push_front(2);
+		list.push_front(1);
+		list.push_back(3);
+		list.push_back(4);
+		assert_eq!(list.len(), 4);
+
+		assert_eq!(list.pop_front(), Some(1));
+		assert_eq!(list.pop_back(), Some(4));
+		assert_eq!(list.pop_back(), Some(3));
+		assert_eq!(list.pop_front(), Some(2));
+		assert_eq!(list.pop_front(), None);
+		assert_eq!(list.pop_back(), None);
+		assert!(list.is_empty());
+	}
+
+	#[test]
+	fn remove_if_and_contains() {
+		let list = SyncList::new();
+		for i in 0..10 {
+			list.push_back(i);
+		}
+		assert!(list.contains(&7));
+		assert_eq!(list.remove_if(|x| x % 2 == 1), 5);
+		assert!(!list.contains(&7));
+		assert_eq!(list.len(), 5);
+
+		for i in (0..10).step_by(2) {
+			assert_eq!(list.pop_front(), Some(i));
+		}
+		assert_eq!(list.pop_back(), None);
+	}
+
+	#[test]
+	fn stress() {
+		const THREADS: usize = 4;
+		const PER_THREAD: usize = 2000;
+
+		let list = Arc::new(SyncList::new());
+		let mut producers = Vec::new();
+		for t in 0..THREADS {
+			let list = list.clone();
+			producers.push(thread::spawn(move || {
+				for i in 0..PER_THREAD {
+					let value = t * PER_THREAD + i;
+					if i % 2 == 0 {
+						list.push_back(value);
+					} else {
+						list.push_front(value);
+					}
+				}
+			}));
+		}
+
+		let mut consumers = Vec::new();
+		for t in 0..THREADS {
+			let list = list.clone();
+			consumers.push(thread::spawn(move || {
+				let mut popped = Vec::new();
+				for i in 0..PER_THREAD {
+					let elem = if (t + i) % 2 == 0 { list.pop_front() } else { list.pop_back() };
+					popped.extend(elem);
+					if i % 100 == 0 {
+						list.contains(&usize::MAX);
+					}
+				}
+				popped
+			}));
+		}
+
+		//remove_if与其他操作并发执行，删除的值单独计数
+		let remover = {
+			let list = list.clone();
+			thread::spawn(move || {
+				let mut removed = 0;
+				for _ in 0..20 {
+					removed += list.remove_if(|x| x % 97 == 0);
+				}
+				removed
+			})
+		};
+
+		for p in producers {
+			p.join().unwrap();
+		}
+		let mut seen: Vec<usize> = Vec::new();
+		for c in consumers {
+			seen.extend(c.join().unwrap());
+		}
+		let removed = remover.join().unwrap();
+		while let Some(x) = list.pop_front() {
+			seen.push(x);
+		}
+
+		assert_eq!(seen.len() + removed, THREADS * PER_THREAD);
+		seen.sort();
+		seen.dedup();
+		assert_eq!(seen.len() + removed, THREADS * PER_THREAD);
+		assert!(list.is_empty());
+	}
+
+	//push_back读出的最后一个节点在加锁前可能被pop_back摘掉
+	#[test]
+	fn push_back_pop_back() {
+		const ROUNDS: usize = 200;
+		const PER_THREAD: usize = 500;
+
+		for _ in 0..ROUNDS {
+			let list = Arc::new(SyncList::new());
+			for i in 0..4 {
+				list.push_back(i);
+			}
+			let mut handles = Vec::new();
+			for t in 0..4 {
+				let list = list.clone();
+				handles.push(thread::spawn(move || {
+					let mut popped = 0;
+					for i in 0..PER_THREAD {
+						if t % 2 == 0 {
+							list.push_back(i);
+						} else if list.pop_back().is_some() {
+							popped += 1;
+						}
+					}
+					popped
+				}));
+			}
+			let popped: usize = handles.into_iter().map(|h| h.join().unwrap()).sum();
+			assert_eq!(list.len() + popped, 4 + 2 * PER_THREAD);
+		}
+	}
+}
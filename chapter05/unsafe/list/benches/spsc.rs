@@ -0,0 +1,62 @@
+//cargo bench --bench spsc
+//比较spsc::channel与std::sync::mpsc在两个线程之间传递消息的耗时
+use list::spsc;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const MESSAGES: usize = 1_000_000;
+const ROUNDS: usize = 5;
+
+fn bench_spsc() -> Duration {
+	let (mut tx, mut rx) = spsc::channel();
+	let start = Instant::now();
+	let producer = thread::spawn(move || {
+		for i in 0..MESSAGES {
+			tx.push(i);
+		}
+	});
+	let mut received = 0;
+	while received < MESSAGES {
+		match rx.pop() {
+			Some(_) => received += 1,
+			None => std::hint::spin_loop(),
+		}
+	}
+	producer.join().unwrap();
+	start.elapsed()
+}
+
+fn bench_mpsc() -> Duration {
+	let (tx, rx) = mpsc::channel();
+	let start = Instant::now();
+	let producer = thread::spawn(move || {
+		for i in 0..MESSAGES {
+			tx.send(i).unwrap();
+		}
+	});
+	let mut received = 0;
+	while received < MESSAGES {
+		match rx.try_recv() {
+			Ok(_) => received += 1,
+			Err(_) => std::hint::spin_loop(),
+		}
+	}
+	producer.join().unwrap();
+	start.elapsed()
+}
+
+fn report(name: &str, f: fn() -> Duration) {
+	let best = (0..ROUNDS).map(|_| f()).min().unwrap();
+	println!(
+		"{:<16} {:>10.2?}  {:>8.1} ns/msg",
+		name,
+		best,
+		best.as_nanos() as f64 / MESSAGES as f64
+	);
+}
+
+fn main() {
+	report("spsc::channel", bench_spsc);
+	report("mpsc::channel", bench_mpsc);
+}
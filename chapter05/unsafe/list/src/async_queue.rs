@@ -0,0 +1,257 @@
+//异步队列：recv()返回Future，队列为空时登记Waker，send或close时唤醒
+//和BlockingQueue一样，close之后不能再send，recv先取完剩余元素再返回None。
+use super::List;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Mutex, MutexGuard};
+use std::task::{Context, Poll, Waker};
+
+pub struct AsyncQueue<T> {
+	state: Mutex<State<T>>,
+}
+
+struct State<T> {
+	list: List<T>,
+	wakers: List<Waker>, //等待中的接收者，也用链表队列保存
+	closed: bool,
+}
+
+//在释放锁之后再唤醒，避免Waker里同步poll时重入死锁
+fn wake_all(mut wakers: List<Waker>) {
+	while let Some(waker) = wakers.pop() {
+		waker.wake();
+	}
+}
+
+impl<T> Default for AsyncQueue<T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T> AsyncQueue<T> {
+	pub fn new() -> Self {
+		AsyncQueue {
+			state: Mutex::new(State { list: List::new(), wakers: List::new(), closed: false }),
+		}
+	}
+
+	fn lock(&self) -> MutexGuard<'_, State<T>> {
+		self.state.lock().unwrap()
+	}
+
+	//已关闭时把元素原样返回
+	pub fn send(&self, elem: T) -> Result<(), T> {
+		let mut state = self.lock();
+		if state.closed {
+			return Err(elem);
+		}
+		state.list.push(elem);
+		//登记过的Future可能已经被丢弃，只唤醒一个可能丢失通知，所以全部唤醒
+		let wakers = std::mem::take(&mut state.wakers);
+		drop(state);
+		wake_all(wakers);
+		Ok(())
+	}
+
+	pub fn recv(&self) -> Recv<'_, T> {
+		Recv { queue: self }
+	}
+
+	//类似Stream::poll_next：Ready(None)表示已关闭且取完
+	pub fn poll_next(&self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+		let mut state = self.lock();
+		if let Some(elem) = state.list.pop() {
+			return Poll::Ready(Some(elem));
+		}
+		if state.closed {
+			return Poll::Ready(None);
+		}
+		//同一个任务反复poll时只登记一次，否则wakers会一直增长到下一次send
+		if !state.wakers.iter().any(|w| w.will_wake(cx.waker())) {
+			state.wakers.push(cx.waker().clone());
+		}
+		Poll::Pending
+	}
+
+	pub fn try_recv(&self) -> Option<T> {
+		self.lock().list.pop()
+	}
+
+	pub fn close(&self) {
+		let mut state = self.lock();
+		state.closed = true;
+		let wakers = std::mem::take(&mut state.wakers);
+		drop(state);
+		wake_all(wakers);
+	}
+
+	pub fn is_closed(&self) -> bool {
+		self.lock().closed
+	}
+}
+
+pub struct Recv<'a, T> {
+	queue: &'a AsyncQueue<T>,
+}
+
+impl<'a, T> Future for Recv<'a, T> {
+	type Output = Option<T>;
+	fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+		self.queue.poll_next(cx)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::AsyncQueue;
+	use std::future::Future;
+	use std::pin::Pin;
+	use std::rc::Rc;
+	use std::sync::{Arc, Mutex};
+	use std::task::{Context, Poll, Wake, Waker};
+
+	//极简单线程执行器：被唤醒的任务编号放进ready队列，依次poll直到全部完成
+	struct TaskWaker {
+		id: usize,
+		ready: Arc<Mutex<Vec<usize>>>,
+	}
+
+	impl Wake for TaskWaker {
+		fn wake(self: Arc<Self>) {
+			self.ready.lock().unwrap().push(self.id);
+		}
+	}
+
+	fn run(tasks: Vec<Pin<Box<dyn Future<Output = ()>>>>) {
+		let ready = Arc::new(Mutex::new((0..tasks.len()).rev().collect::<Vec<_>>()));
+		let mut tasks: Vec<_> = tasks.into_iter().map(Some).collect();
+		loop {
+			let id = match ready.lock().unwrap().pop() {
+				Some(id) => id,
+				None => break,
+			};
+			if let Some(task) = tasks[id].as_mut() {
+				let waker = Waker::from(Arc::new(TaskWaker { id, ready: ready.clone() }));
+				if task.as_mut().poll(&mut Context::from_waker(&waker)).is_ready() {
+					tasks[id] = None;
+				}
+			}
+		}
+		assert!(tasks.iter().all(|t| t.is_none()), "executor stalled");
+	}
+
+	//让出一次执行权
+	struct YieldNow(bool);
+
+	impl Future for YieldNow {
+		type Output = ();
+		fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+			if self.0 {
+				return Poll::Ready(());
+			}
+			self.0 = true;
+			cx.waker().wake_by_ref();
+			Poll::Pending
+		}
+	}
+
+	#[test]
+	fn recv_ready() {
+		let queue = AsyncQueue::new();
+		queue.send(1).unwrap();
+		queue.send(2).unwrap();
+		queue.close();
+		assert_eq!(queue.send(3), Err(3));
+
+		let got = Rc::new(Mutex::new(Vec::new()));
+		let (q, g) = (Rc::new(queue), got.clone());
+		run(vec![Box::pin(async move {
+			while let Some(x) = q.recv().await {
+				g.lock().unwrap().push(x);
+			}
+		})]);
+		assert_eq!(*got.lock().unwrap(), vec![1, 2]);
+	}
+
+	#[test]
+	fn producer_consumer_tasks() {
+		let queue = Rc::new(AsyncQueue::new());
+		let got = Rc::new(Mutex::new(Vec::new()));
+
+		let consumer = {
+			let (queue, got) = (queue.clone(), got.clone());
+			async move {
+				while let Some(x) = queue.recv().await {
+					got.lock().unwrap().push(x);
+				}
+			}
+		};
+		let producer = {
+			let queue = queue.clone();
+			async move {
+				for i in 0..10 {
+					queue.send(i).unwrap();
+					YieldNow(false).await;
+				}
+				queue.close();
+			}
+		};
+		run(vec![Box::pin(consumer), Box::pin(producer)]);
+		assert_eq!(*got.lock().unwrap(), (0..10).collect::<Vec<_>>());
+	}
+
+	#[test]
+	fn close_wakes_all_receivers() {
+		let queue = Rc::new(AsyncQueue::<i32>::new());
+		let finished = Rc::new(Mutex::new(0));
+		let mut tasks: Vec<Pin<Box<dyn Future<Output = ()>>>> = Vec::new();
+		for _ in 0..3 {
+			let (queue, finished) = (queue.clone(), finished.clone());
+			tasks.push(Box::pin(async move {
+				assert_eq!(queue.recv().await, None);
+				*finished.lock().unwrap() += 1;
+			}));
+		}
+		let closer = queue.clone();
+		tasks.push(Box::pin(async move {
+			YieldNow(false).await;
+			closer.close();
+		}));
+		run(tasks);
+		assert_eq!(*finished.lock().unwrap(), 3);
+	}
+
+	#[test]
+	fn poll_next_registers_waker() {
+		let queue = AsyncQueue::new();
+		let woken = Arc::new(Mutex::new(Vec::new()));
+		let waker = Waker::from(Arc::new(TaskWaker { id: 7, ready: woken.clone() }));
+		let mut cx = Context::from_waker(&waker);
+
+		assert_eq!(queue.poll_next(&mut cx), Poll::Pending);
+		assert!(woken.lock().unwrap().is_empty());
+		queue.send(5).unwrap();
+		assert_eq!(*woken.lock().unwrap(), vec![7]);
+		assert_eq!(queue.poll_next(&mut cx), Poll::Ready(Some(5)));
+		queue.close();
+		assert_eq!(queue.poll_next(&mut cx), Poll::Ready(None));
+	}
+
+	#[test]
+	fn repoll_does_not_grow_wakers() {
+		let queue = AsyncQueue::<i32>::new();
+		let ready = Arc::new(Mutex::new(Vec::new()));
+		let a = Waker::from(Arc::new(TaskWaker { id: 1, ready: ready.clone() }));
+		let b = Waker::from(Arc::new(TaskWaker { id: 2, ready: ready.clone() }));
+
+		for _ in 0..100 {
+			assert_eq!(queue.poll_next(&mut Context::from_waker(&a)), Poll::Pending);
+			assert_eq!(queue.poll_next(&mut Context::from_waker(&b)), Poll::Pending);
+		}
+		assert_eq!(queue.lock().wakers.len(), 2);
+		queue.send(1).unwrap();
+		assert_eq!(*ready.lock().unwrap(), vec![1, 2]);
+		assert!(queue.lock().wakers.is_empty());
+	}
+}
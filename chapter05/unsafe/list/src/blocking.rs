@@ -0,0 +1,208 @@
+//阻塞队列：用Mutex + Condvar包装List，供生产者/消费者线程之间传递数据
+//close之后不能再push，pop会先把剩余元素取完再返回None。
+use super::List;
+use std::sync::{Condvar, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+pub struct BlockingQueue<T> {
+	state: Mutex<State<T>>,
+	not_empty: Condvar,
+}
+
+struct State<T> {
+	list: List<T>,
+	closed: bool,
+}
+
+impl<T> Default for BlockingQueue<T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T> BlockingQueue<T> {
+	pub fn new() -> Self {
+		BlockingQueue {
+			state: Mutex::new(State { list: List::new(), closed: false }),
+			not_empty: Condvar::new(),
+		}
+	}
+
+	fn lock(&self) -> MutexGuard<'_, State<T>> {
+		self.state.lock().unwrap()
+	}
+
+	//已关闭时把元素原样返回
+	pub fn push(&self, elem: T) -> Result<(), T> {
+		let mut state = self.lock();
+		if state.closed {
+			return Err(elem);
+		}
+		state.list.push(elem);
+		self.not_empty.notify_one();
+		Ok(())
+	}
+
+	//阻塞直到有元素，或者队列已关闭且为空
+	pub fn pop(&self) -> Option<T> {
+		let mut state = self.lock();
+		loop {
+			if let Some(elem) = state.list.pop() {
+				return Some(elem);
+			}
+			if state.closed {
+				return None;
+			}
+			state = self.not_empty.wait(state).unwrap();
+		}
+	}
+
+	//超时返回None；timeout大到算不出截止时间时和pop一样一直等
+	pub fn pop_timeout(&self, timeout: Duration) -> Option<T> {
+		let deadline = match Instant::now().checked_add(timeout) {
+			Some(deadline) => deadline,
+			None => return self.pop(),
+		};
+		let mut state = self.lock();
+		loop {
+			if let Some(elem) = state.list.pop() {
+				return Some(elem);
+			}
+			let now = Instant::now();
+			if state.closed || now >= deadline {
+				return None;
+			}
+			state = self.not_empty.wait_timeout(state, deadline - now).unwrap().0;
+		}
+	}
+
+	pub fn try_pop(&self) -> Option<T> {
+		self.lock().list.pop()
+	}
+
+	//阻塞直到至少有一个元素，然后一次取出最多max个；关闭且为空时返回空Vec
+	pub fn drain_batch(&self, max: usize) -> Vec<T> {
+		let mut batch = Vec::new();
+		if max == 0 {
+			return batch;
+		}
+		let mut state = self.lock();
+		loop {
+			while batch.len() < max {
+				match state.list.pop() {
+					Some(elem) => batch.push(elem),
+					None => break,
+				}
+			}
+			if !batch.is_empty() || state.closed {
+				return batch;
+			}
+			state = self.not_empty.wait(state).unwrap();
+		}
+	}
+
+	//关闭队列并唤醒所有等待的消费者
+	pub fn close(&self) {
+		self.lock().closed = true;
+		self.not_empty.notify_all();
+	}
+
+	pub fn is_closed(&self) -> bool {
+		self.lock().closed
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::BlockingQueue;
+	use std::sync::Arc;
+	use std::thread;
+	use std::time::Duration;
+
+	#[test]
+	fn basics() {
+		let queue = BlockingQueue::new();
+		assert_eq!(queue.try_pop(), None);
+		queue.push(1).unwrap();
+		queue.push(2).unwrap();
+		assert_eq!(queue.pop(), Some(1));
+		assert_eq!(queue.try_pop(), Some(2));
+		assert_eq!(queue.pop_timeout(Duration::from_millis(10)), None);
+	}
+
+	#[test]
+	fn close() {
+		let queue = BlockingQueue::new();
+		queue.push(1).unwrap();
+		queue.close();
+		assert!(queue.is_closed());
+		assert_eq!(queue.push(2), Err(2));
+		assert_eq!(queue.pop(), Some(1));
+		assert_eq!(queue.pop(), None);
+		assert_eq!(queue.pop_timeout(Duration::from_secs(10)), None);
+		assert!(queue.drain_batch(4).is_empty());
+	}
+
+	#[test]
+	fn close_wakes_waiters() {
+		let queue = Arc::new(BlockingQueue::<i32>::new());
+		let waiters: Vec<_> = (0..3).map(|_| {
+			let queue = queue.clone();
+			thread::spawn(move || queue.pop())
+		}).collect();
+		thread::sleep(Duration::from_millis(20));
+		queue.close();
+		for w in waiters {
+			assert_eq!(w.join().unwrap(), None);
+		}
+	}
+
+	#[test]
+	fn producer_consumer() {
+		let queue = Arc::new(BlockingQueue::new());
+		let producer = {
+			let queue = queue.clone();
+			thread::spawn(move || {
+				for i in 0..1000 {
+					queue.push(i).unwrap();
+				}
+				queue.close();
+			})
+		};
+
+		let mut got = Vec::new();
+		loop {
+			let batch = queue.drain_batch(64);
+			if batch.is_empty() {
+				break;
+			}
+			assert!(batch.len() <= 64);
+			got.extend(batch);
+		}
+		producer.join().unwrap();
+		assert_eq!(got, (0..1000).collect::<Vec<_>>());
+	}
+
+	#[test]
+	fn pop_timeout_receives() {
+		let queue = Arc::new(BlockingQueue::new());
+		let producer = {
+			let queue = queue.clone();
+			thread::spawn(move || {
+				thread::sleep(Duration::from_millis(20));
+				queue.push(7).unwrap();
+			})
+		};
+		assert_eq!(queue.pop_timeout(Duration::from_secs(10)), Some(7));
+		producer.join().unwrap();
+	}
+
+	#[test]
+	fn pop_timeout_max() {
+		let queue = BlockingQueue::new();
+		queue.push(1).unwrap();
+		assert_eq!(queue.pop_timeout(Duration::MAX), Some(1));
+		queue.close();
+		assert_eq!(queue.pop_timeout(Duration::MAX), None);
+	}
+}
@@ -0,0 +1,132 @@
+//有界队列
+//容量满时try_push把元素原样返回，push_overwrite则挤掉最旧的元素，
+//并直接复用被挤掉的节点，和环形缓冲区一样不需要重新分配。
+use super::{Iter, List, Node};
+
+pub struct BoundedQueue<T> {
+	list: List<T>,
+	capacity: usize,
+}
+
+impl<T> BoundedQueue<T> {
+	pub fn new(capacity: usize) -> Self {
+		BoundedQueue { list: List::new(), capacity }
+	}
+
+	pub fn capacity(&self) -> usize {
+		self.capacity
+	}
+
+	pub fn len(&self) -> usize {
+		self.list.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.list.is_empty()
+	}
+
+	pub fn is_full(&self) -> bool {
+		self.list.len() >= self.capacity
+	}
+
+	pub fn try_push(&mut self, elem: T) -> Result<(), T> {
+		if self.is_full() {
+			return Err(elem);
+		}
+		self.list.push(elem);
+		Ok(())
+	}
+
+	//满了就把最旧的元素挤出去并返回
+	pub fn push_overwrite(&mut self, elem: T) -> Option<T> {
+		if !self.is_full() {
+			self.list.push(elem);
+			return None;
+		}
+		//容量为0时新元素本身就是被挤掉的那个
+		let mut node: Box<Node<T>> = match self.list.pop_node() {
+			Some(node) => node,
+			None => return Some(elem),
+		};
+		let oldest = std::mem::replace(&mut node.elem, elem);
+		self.list.push_node(node);
+		Some(oldest)
+	}
+
+	pub fn pop(&mut self) -> Option<T> {
+		self.list.pop()
+	}
+
+	//修改容量，超出新容量的最旧元素按原顺序放在返回的List中
+	pub fn set_capacity(&mut self, capacity: usize) -> List<T> {
+		let mut excess = List::new();
+		while self.list.len() > capacity {
+			let node = self.list.pop_node().unwrap();
+			excess.push_node(node);
+		}
+		self.capacity = capacity;
+		excess
+	}
+
+	pub fn iter(&self) -> Iter<'_, T> {
+		self.list.iter()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::BoundedQueue;
+
+	#[test]
+	fn basics() {
+		let mut queue = BoundedQueue::new(2);
+		assert_eq!(queue.capacity(), 2);
+		assert!(queue.is_empty());
+		assert_eq!(queue.try_push(1), Ok(()));
+		assert_eq!(queue.try_push(2), Ok(()));
+		assert!(queue.is_full());
+		assert_eq!(queue.try_push(3), Err(3));
+
+		assert_eq!(queue.pop(), Some(1));
+		assert_eq!(queue.try_push(3), Ok(()));
+		assert_eq!(queue.pop(), Some(2));
+		assert_eq!(queue.pop(), Some(3));
+		assert_eq!(queue.pop(), None);
+	}
+
+	#[test]
+	fn overwrite() {
+		let mut queue = BoundedQueue::new(3);
+		for i in 0..3 {
+			assert_eq!(queue.push_overwrite(i), None);
+		}
+		assert_eq!(queue.push_overwrite(3), Some(0));
+		assert_eq!(queue.push_overwrite(4), Some(1));
+		assert_eq!(queue.len(), 3);
+		assert_eq!(queue.iter().collect::<Vec<_>>(), vec![&2, &3, &4]);
+
+		let mut empty = BoundedQueue::new(0);
+		assert_eq!(empty.push_overwrite(1), Some(1));
+		assert_eq!(empty.try_push(1), Err(1));
+	}
+
+	#[test]
+	fn set_capacity() {
+		let mut queue = BoundedQueue::new(5);
+		for i in 0..5 {
+			queue.try_push(i).unwrap();
+		}
+
+		assert!(queue.set_capacity(8).into_iter().next().is_none());
+		assert_eq!(queue.capacity(), 8);
+		assert!(!queue.is_full());
+
+		let excess = queue.set_capacity(2);
+		assert_eq!(excess.into_iter().collect::<Vec<_>>(), vec![0, 1, 2]);
+		assert_eq!(queue.len(), 2);
+		assert!(queue.is_full());
+		assert_eq!(queue.push_overwrite(5), Some(3));
+		assert_eq!(queue.pop(), Some(4));
+		assert_eq!(queue.pop(), Some(5));
+	}
+}
@@ -0,0 +1,331 @@
+//无锁并发队列（Michael-Scott算法）
+//和List一样从head出队、从tail入队，区别是head/tail都是原子指针，
+//并且head始终指向一个哑节点(dummy)，真正的队首元素存放在dummy.next中。
+//出队时head前移，新的head成为dummy，旧的dummy被回收。
+//
+//内存回收使用hazard pointer：线程在解引用节点前先把指针登记到自己的hazard槽里，
+//被摘下的节点先放进retired列表，只有不在任何hazard槽中时才真正释放。
+use std::cell::UnsafeCell;
+use std::marker::PhantomData;
+use std::mem::MaybeUninit;
+use std::ptr;
+use std::sync::atomic::{AtomicBool, AtomicPtr, Ordering};
+
+pub struct ConcurrentQueue<T> {
+	head: AtomicPtr<Node<T>>,
+	tail: AtomicPtr<Node<T>>,
+	records: AtomicPtr<Record<T>>, //hazard记录链表，只增不减，队列析构时统一释放
+	marker: PhantomData<T>,
+}
+
+struct Node<T> {
+	elem: UnsafeCell<MaybeUninit<T>>, //dummy节点中的elem未初始化或已被移走
+	next: AtomicPtr<Node<T>>,
+}
+
+impl<T> Node<T> {
+	fn alloc(elem: MaybeUninit<T>) -> *mut Self {
+		Box::into_raw(Box::new(Node {
+			elem: UnsafeCell::new(elem),
+			next: AtomicPtr::new(ptr::null_mut()),
+		}))
+	}
+}
+
+//retired列表达到这个长度时扫描一次hazard槽
+const RETIRE_THRESHOLD: usize = 64;
+
+struct Record<T> {
+	active: AtomicBool, //是否正被某个线程使用
+	hazards: [AtomicPtr<Node<T>>; 2],
+	retired: UnsafeCell<Vec<*mut Node<T>>>, //只有持有该记录的线程才能访问
+	next: *mut Record<T>,
+}
+
+//一次push/pop期间占用的hazard记录，drop时清空hazard并归还记录
+struct Hazards<'a, T> {
+	queue: &'a ConcurrentQueue<T>,
+	record: &'a Record<T>,
+}
+
+impl<'a, T> Hazards<'a, T> {
+	fn set(&self, i: usize, node: *mut Node<T>) {
+		self.record.hazards[i].store(node, Ordering::SeqCst);
+	}
+
+	fn retire(&self, node: *mut Node<T>) {
+		let retired = unsafe { &mut *self.record.retired.get() };
+		retired.push(node);
+		if retired.len() >= RETIRE_THRESHOLD {
+			self.queue.scan(retired);
+		}
+	}
+}
+
+impl<'a, T> Drop for Hazards<'a, T> {
+	fn drop(&mut self) {
+		self.set(0, ptr::null_mut());
+		self.set(1, ptr::null_mut());
+		self.record.active.store(false, Ordering::Release);
+	}
+}
+
+unsafe impl<T: Send> Send for ConcurrentQueue<T> {}
+unsafe impl<T: Send> Sync for ConcurrentQueue<T> {}
+
+impl<T> Default for ConcurrentQueue<T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T> ConcurrentQueue<T> {
+	pub fn new() -> Self {
+		let dummy = Node::alloc(MaybeUninit::uninit());
+		ConcurrentQueue {
+			head: AtomicPtr::new(dummy),
+			tail: AtomicPtr::new(dummy),
+			records: AtomicPtr::new(ptr::null_mut()),
+			marker: PhantomData,
+		}
+	}
+
+	//找一个空闲的hazard记录，没有就新建一个挂到链表头
+	fn hazards(&self) -> Hazards<'_, T> {
+		let mut p = self.records.load(Ordering::Acquire);
+		while !p.is_null() {
+			let record = unsafe { &*p };
+			if !record.active.load(Ordering::Relaxed)
+				&& record.active
+					.compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+					.is_ok()
+			{
+				return Hazards { queue: self, record };
+			}
+			p = record.next;
+		}
+
+		let record = Box::into_raw(Box::new(Record {
+			active: AtomicBool::new(true),
+			hazards: [AtomicPtr::new(ptr::null_mut()), AtomicPtr::new(ptr::null_mut())],
+			retired: UnsafeCell::new(Vec::new()),
+			next: ptr::null_mut(),
+		}));
+		let mut head = self.records.load(Ordering::Acquire);
+		loop {
+			unsafe { (*record).next = head };
+			match self.records.compare_exchange(head, record, Ordering::AcqRel, Ordering::Acquire) {
+				Ok(_) => break,
+				Err(actual) => head = actual,
+			}
+		}
+		Hazards { queue: self, record: unsafe { &*record } }
+	}
+
+	//释放retired中不再被任何线程保护的节点
+	fn scan(&self, retired: &mut Vec<*mut Node<T>>) {
+		let mut protected = Vec::new();
+		let mut p = self.records.load(Ordering::Acquire);
+		while !p.is_null() {
+			let record = unsafe { &*p };
+			for hazard in &record.hazards {
+				let node = hazard.load(Ordering::SeqCst);
+				if !node.is_null() {
+					protected.push(node);
+				}
+			}
+			p = record.next;
+		}
+		retired.retain(|&node| {
+			if protected.contains(&node) {
+				true
+			} else {
+				unsafe { drop(Box::from_raw(node)) };
+				false
+			}
+		});
+	}
+
+	pub fn push(&self, elem: T) {
+		let node = Node::alloc(MaybeUninit::new(elem));
+		let hazards = self.hazards();
+		loop {
+			let tail = self.tail.load(Ordering::SeqCst);
+			hazards.set(0, tail);
+			if self.tail.load(Ordering::SeqCst) != tail {
+				continue;
+			}
+			let next = unsafe { (*tail).next.load(Ordering::SeqCst) };
+			if !next.is_null() {
+				//tail落后了，帮忙把它往前推
+				let _ = self.tail.compare_exchange(tail, next, Ordering::SeqCst, Ordering::SeqCst);
+				continue;
+			}
+			let linked = unsafe {
+				(*tail).next.compare_exchange(ptr::null_mut(), node, Ordering::SeqCst, Ordering::SeqCst)
+			};
+			if linked.is_ok() {
+				let _ = self.tail.compare_exchange(tail, node, Ordering::SeqCst, Ordering::SeqCst);
+				return;
+			}
+		}
+	}
+
+	pub fn pop(&self) -> Option<T> {
+		let hazards = self.hazards();
+		loop {
+			let head = self.head.load(Ordering::SeqCst);
+			hazards.set(0, head);
+			if self.head.load(Ordering::SeqCst) != head {
+				continue;
+			}
+			let tail = self.tail.load(Ordering::SeqCst);
+			let next = unsafe { (*head).next.load(Ordering::SeqCst) };
+			hazards.set(1, next);
+			//head没变说明next还没有出队，也就不会被回收
+			if self.head.load(Ordering::SeqCst) != head {
+				continue;
+			}
+			if next.is_null() {
+				return None;
+			}
+			if head == tail {
+				let _ = self.tail.compare_exchange(tail, next, Ordering::SeqCst, Ordering::SeqCst);
+				continue;
+			}
+			if self.head.compare_exchange(head, next, Ordering::SeqCst, Ordering::SeqCst).is_ok() {
+				//只有CAS成功的线程会读取next中的元素，next成为新的dummy
+				let elem = unsafe { ptr::read((*next).elem.get()).assume_init() };
+				hazards.retire(head);
+				return Some(elem);
+			}
+		}
+	}
+
+	pub fn is_empty(&self) -> bool {
+		let hazards = self.hazards();
+		loop {
+			let head = self.head.load(Ordering::SeqCst);
+			hazards.set(0, head);
+			if self.head.load(Ordering::SeqCst) == head {
+				return unsafe { (*head).next.load(Ordering::SeqCst) }.is_null();
+			}
+		}
+	}
+}
+
+impl<T> Drop for ConcurrentQueue<T> {
+	fn drop(&mut self) {
+		//dummy之后的节点还持有元素，需要一并析构
+		let dummy = unsafe { Box::from_raw(*self.head.get_mut()) };
+		let mut node = dummy.next.load(Ordering::Relaxed);
+		while !node.is_null() {
+			let mut boxed = unsafe { Box::from_raw(node) };
+			node = boxed.next.load(Ordering::Relaxed);
+			unsafe { boxed.elem.get_mut().assume_init_drop() };
+		}
+
+		let mut record = *self.records.get_mut();
+		while !record.is_null() {
+			let boxed = unsafe { Box::from_raw(record) };
+			for &node in unsafe { &*boxed.retired.get() } {
+				unsafe { drop(Box::from_raw(node)) };
+			}
+			record = boxed.next;
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::ConcurrentQueue;
+	use std::sync::atomic::{AtomicUsize, Ordering};
+	use std::sync::Arc;
+	use std::thread;
+
+	#[test]
+	fn basics() {
+		let queue = ConcurrentQueue::new();
+		assert!(queue.is_empty());
+		assert_eq!(queue.pop(), None);
+
+		queue.push(1);
+		queue.push(2);
+		queue.push(3);
+		assert!(!queue.is_empty());
+		assert_eq!(queue.pop(), Some(1));
+		assert_eq!(queue.pop(), Some(2));
+
+		queue.push(4);
+		assert_eq!(queue.pop(), Some(3));
+		assert_eq!(queue.pop(), Some(4));
+		assert_eq!(queue.pop(), None);
+	}
+
+	#[test]
+	fn drop_remaining() {
+		let elem = Arc::new(());
+		let queue = ConcurrentQueue::new();
+		for _ in 0..200 {
+			queue.push(elem.clone());
+		}
+		for _ in 0..150 {
+			queue.pop();
+		}
+		assert_eq!(Arc::strong_count(&elem), 51);
+		drop(queue);
+		assert_eq!(Arc::strong_count(&elem), 1);
+	}
+
+	#[test]
+	fn stress() {
+		const PRODUCERS: usize = 4;
+		const CONSUMERS: usize = 4;
+		const PER_PRODUCER: usize = 10000;
+
+		let queue = Arc::new(ConcurrentQueue::new());
+		let popped = Arc::new(AtomicUsize::new(0));
+
+		let producers: Vec<_> = (0..PRODUCERS).map(|p| {
+			let queue = queue.clone();
+			thread::spawn(move || {
+				for seq in 0..PER_PRODUCER {
+					queue.push((p, seq));
+				}
+			})
+		}).collect();
+
+		let consumers: Vec<_> = (0..CONSUMERS).map(|_| {
+			let queue = queue.clone();
+			let popped = popped.clone();
+			thread::spawn(move || {
+				let mut got = Vec::new();
+				while popped.load(Ordering::SeqCst) < PRODUCERS * PER_PRODUCER {
+					if let Some(item) = queue.pop() {
+						popped.fetch_add(1, Ordering::SeqCst);
+						got.push(item);
+					}
+				}
+				got
+			})
+		}).collect();
+
+		for p in producers {
+			p.join().unwrap();
+		}
+
+		let mut seen = vec![vec![false; PER_PRODUCER]; PRODUCERS];
+		for c in consumers {
+			//同一个消费者看到的同一生产者的元素必须按入队顺序出现
+			let mut last = [None; PRODUCERS];
+			for (p, seq) in c.join().unwrap() {
+				assert!(last[p] < Some(seq));
+				last[p] = Some(seq);
+				assert!(!seen[p][seq], "popped twice");
+				seen[p][seq] = true;
+			}
+		}
+		assert!(seen.iter().all(|s| s.iter().all(|&x| x)));
+		assert!(queue.is_empty());
+	}
+}
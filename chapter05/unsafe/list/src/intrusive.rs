@@ -0,0 +1,474 @@
+//侵入式双链表
+//普通链表把元素放进自己的Node里，每个元素多一次分配。
+//侵入式链表反过来：用户在自己的结构体里放一个Link字段，链表直接把这些Link串起来，
+//再通过Adapter从Link找回所在的对象（类似C里的container_of）。
+//
+//链表持有对象的所有权指针（Box、Rc或&'a），入链时转成裸指针，出链时再还原。
+use std::cell::Cell;
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::ptr::NonNull;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+//嵌入到用户结构体中的链接字段
+pub struct Link {
+	next: Cell<Option<NonNull<Link>>>,
+	prev: Cell<Option<NonNull<Link>>>,
+	owner: Cell<usize>, //所在链表的id，0表示未链接
+}
+
+impl Link {
+	pub const fn new() -> Self {
+		Link { next: Cell::new(None), prev: Cell::new(None), owner: Cell::new(0) }
+	}
+
+	pub fn is_linked(&self) -> bool {
+		self.owner.get() != 0
+	}
+}
+
+impl Default for Link {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl fmt::Debug for Link {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Link").field("linked", &self.is_linked()).finish()
+	}
+}
+
+/// 可以和裸指针互相转换的所有权指针
+///
+/// # Safety
+///
+/// into_raw得到的指针在from_raw之前必须一直有效，且指向的对象不能移动。
+pub unsafe trait PointerOps: Deref + Sized {
+	fn into_raw(self) -> *const Self::Target;
+
+	/// # Safety
+	///
+	/// ptr必须来自同类型指针的into_raw，并且每个ptr只能还原一次。
+	unsafe fn from_raw(ptr: *const Self::Target) -> Self;
+}
+
+unsafe impl<T> PointerOps for Box<T> {
+	fn into_raw(self) -> *const T {
+		Box::into_raw(self)
+	}
+	unsafe fn from_raw(ptr: *const T) -> Self {
+		Box::from_raw(ptr as *mut T)
+	}
+}
+
+unsafe impl<T> PointerOps for Rc<T> {
+	fn into_raw(self) -> *const T {
+		Rc::into_raw(self)
+	}
+	unsafe fn from_raw(ptr: *const T) -> Self {
+		Rc::from_raw(ptr)
+	}
+}
+
+unsafe impl<T> PointerOps for &T {
+	fn into_raw(self) -> *const T {
+		self
+	}
+	unsafe fn from_raw(ptr: *const T) -> Self {
+		&*ptr
+	}
+}
+
+/// 对象与其Link之间的映射，一般用intrusive_adapter!生成
+///
+/// # Safety
+///
+/// link必须返回value内部的字段，并且value(link(v))得到的就是v本身。
+pub unsafe trait Adapter {
+	type Value;
+	type Pointer: PointerOps<Target = Self::Value>;
+	fn link(value: &Self::Value) -> &Link;
+
+	/// # Safety
+	///
+	/// link必须来自Self::link返回的引用。
+	unsafe fn value(link: NonNull<Link>) -> *const Self::Value;
+}
+
+//intrusive_adapter!(pub NameAdapter = Box<Name> : Name { link });
+//intrusive_adapter!(pub NameRefAdapter<'a> = &'a Name : Name { link });
+#[macro_export]
+macro_rules! intrusive_adapter {
+	($vis:vis $name:ident = $ptr:ty : $value:ty { $field:ident }) => {
+		$vis struct $name;
+		unsafe impl $crate::intrusive::Adapter for $name {
+			type Value = $value;
+			type Pointer = $ptr;
+			fn link(value: &$value) -> &$crate::intrusive::Link {
+				&value.$field
+			}
+			unsafe fn value(link: ::std::ptr::NonNull<$crate::intrusive::Link>) -> *const $value {
+				(link.as_ptr() as *const u8).sub(::std::mem::offset_of!($value, $field)) as *const $value
+			}
+		}
+	};
+	($vis:vis $name:ident<$lt:lifetime> = $ptr:ty : $value:ty { $field:ident }) => {
+		$vis struct $name<$lt>(::std::marker::PhantomData<&$lt ()>);
+		unsafe impl<$lt> $crate::intrusive::Adapter for $name<$lt> {
+			type Value = $value;
+			type Pointer = $ptr;
+			fn link(value: &$value) -> &$crate::intrusive::Link {
+				&value.$field
+			}
+			unsafe fn value(link: ::std::ptr::NonNull<$crate::intrusive::Link>) -> *const $value {
+				(link.as_ptr() as *const u8).sub(::std::mem::offset_of!($value, $field)) as *const $value
+			}
+		}
+	};
+}
+
+//每个链表有唯一的id，用来判断一个Link是否属于本链表
+static NEXT_ID: AtomicUsize = AtomicUsize::new(1);
+
+pub struct IntrusiveList<A: Adapter> {
+	head: Option<NonNull<Link>>,
+	tail: Option<NonNull<Link>>,
+	len: usize,
+	id: usize,
+	marker: PhantomData<A::Pointer>,
+}
+
+impl<A: Adapter> Default for IntrusiveList<A> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<A: Adapter> IntrusiveList<A> {
+	pub fn new() -> Self {
+		IntrusiveList {
+			head: None,
+			tail: None,
+			len: 0,
+			id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+			marker: PhantomData,
+		}
+	}
+
+	pub fn len(&self) -> usize {
+		self.len
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len == 0
+	}
+
+	//把ptr链接到prev和next之间
+	fn insert_between(&mut self, ptr: A::Pointer, prev: Option<NonNull<Link>>, next: Option<NonNull<Link>>) {
+		assert!(!A::link(&ptr).is_linked(), "object is already linked into a list");
+		let raw = ptr.into_raw();
+		let link = unsafe { A::link(&*raw) };
+		let raw_link = NonNull::from(link);
+		link.owner.set(self.id);
+		link.prev.set(prev);
+		link.next.set(next);
+		unsafe {
+			match prev {
+				Some(prev) => prev.as_ref().next.set(Some(raw_link)),
+				None => self.head = Some(raw_link),
+			}
+			match next {
+				Some(next) => next.as_ref().prev.set(Some(raw_link)),
+				None => self.tail = Some(raw_link),
+			}
+		}
+		self.len += 1;
+	}
+
+	//link必须在本链表中
+	unsafe fn unlink(&mut self, raw_link: NonNull<Link>) -> A::Pointer {
+		let link = raw_link.as_ref();
+		let prev = link.prev.take();
+		let next = link.next.take();
+		match prev {
+			Some(prev) => prev.as_ref().next.set(next),
+			None => self.head = next,
+		}
+		match next {
+			Some(next) => next.as_ref().prev.set(prev),
+			None => self.tail = prev,
+		}
+		link.owner.set(0);
+		self.len -= 1;
+		A::Pointer::from_raw(A::value(raw_link))
+	}
+
+	pub fn push_front(&mut self, ptr: A::Pointer) {
+		self.insert_between(ptr, None, self.head);
+	}
+
+	pub fn push_back(&mut self, ptr: A::Pointer) {
+		self.insert_between(ptr, self.tail, None);
+	}
+
+	pub fn pop_front(&mut self) -> Option<A::Pointer> {
+		self.head.map(|link| unsafe { self.unlink(link) })
+	}
+
+	pub fn pop_back(&mut self) -> Option<A::Pointer> {
+		self.tail.map(|link| unsafe { self.unlink(link) })
+	}
+
+	pub fn front(&self) -> Option<&A::Value> {
+		self.head.map(|link| unsafe { &*A::value(link) })
+	}
+
+	pub fn back(&self) -> Option<&A::Value> {
+		self.tail.map(|link| unsafe { &*A::value(link) })
+	}
+
+	pub fn contains(&self, value: &A::Value) -> bool {
+		A::link(value).owner.get() == self.id
+	}
+
+	//按引用删除，value不在本链表中时返回None
+	pub fn remove(&mut self, value: &A::Value) -> Option<A::Pointer> {
+		if !self.contains(value) {
+			return None;
+		}
+		Some(unsafe { self.unlink(NonNull::from(A::link(value))) })
+	}
+
+	pub fn iter(&self) -> Iter<'_, A> {
+		Iter { next: self.head, marker: PhantomData }
+	}
+
+	pub fn cursor_mut(&mut self) -> CursorMut<'_, A> {
+		CursorMut { current: self.head, list: self }
+	}
+}
+
+impl<A: Adapter> Drop for IntrusiveList<A> {
+	fn drop(&mut self) {
+		while self.pop_front().is_some() {}
+	}
+}
+
+//Iter
+pub struct Iter<'a, A: Adapter> {
+	next: Option<NonNull<Link>>,
+	marker: PhantomData<&'a A::Value>,
+}
+
+impl<'a, A: Adapter> Iterator for Iter<'a, A> {
+	type Item = &'a A::Value;
+	fn next(&mut self) -> Option<Self::Item> {
+		self.next.map(|link| unsafe {
+			self.next = link.as_ref().next.get();
+			&*A::value(link)
+		})
+	}
+}
+
+//可修改链表的游标，current为None表示位于尾部之后、头部之前的空位置
+pub struct CursorMut<'a, A: Adapter> {
+	list: &'a mut IntrusiveList<A>,
+	current: Option<NonNull<Link>>,
+}
+
+impl<'a, A: Adapter> CursorMut<'a, A> {
+	pub fn get(&self) -> Option<&A::Value> {
+		self.current.map(|link| unsafe { &*A::value(link) })
+	}
+
+	pub fn move_next(&mut self) {
+		self.current = match self.current {
+			Some(link) => unsafe { link.as_ref().next.get() },
+			None => self.list.head,
+		};
+	}
+
+	pub fn move_prev(&mut self) {
+		self.current = match self.current {
+			Some(link) => unsafe { link.as_ref().prev.get() },
+			None => self.list.tail,
+		};
+	}
+
+	//删除当前对象，游标移到下一个
+	pub fn remove_current(&mut self) -> Option<A::Pointer> {
+		let link = self.current?;
+		unsafe {
+			self.current = link.as_ref().next.get();
+			Some(self.list.unlink(link))
+		}
+	}
+
+	//在空位置时插入到链表末尾
+	pub fn insert_before(&mut self, ptr: A::Pointer) {
+		match self.current {
+			Some(link) => {
+				let prev = unsafe { link.as_ref().prev.get() };
+				self.list.insert_between(ptr, prev, Some(link));
+			}
+			None => self.list.push_back(ptr),
+		}
+	}
+
+	//在空位置时插入到链表开头
+	pub fn insert_after(&mut self, ptr: A::Pointer) {
+		match self.current {
+			Some(link) => {
+				let next = unsafe { link.as_ref().next.get() };
+				self.list.insert_between(ptr, Some(link), next);
+			}
+			None => self.list.push_front(ptr),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{IntrusiveList, Link};
+	use std::cell::Cell;
+	use std::rc::Rc;
+
+	#[derive(Debug, Default)]
+	struct Task {
+		id: u32,
+		link: Link,
+		_counter: Option<DropCounter>,
+	}
+
+	impl Task {
+		fn new(id: u32) -> Self {
+			Task { id, ..Default::default() }
+		}
+	}
+
+	#[derive(Debug)]
+	struct DropCounter(Rc<Cell<u32>>);
+
+	impl Drop for DropCounter {
+		fn drop(&mut self) {
+			self.0.set(self.0.get() + 1);
+		}
+	}
+
+	intrusive_adapter!(BoxTask = Box<Task> : Task { link });
+	intrusive_adapter!(RcTask = Rc<Task> : Task { link });
+	intrusive_adapter!(RefTask<'a> = &'a Task : Task { link });
+
+	fn ids<'a>(iter: impl Iterator<Item = &'a Task>) -> Vec<u32> {
+		iter.map(|t| t.id).collect()
+	}
+
+	#[test]
+	fn boxed() {
+		let mut list = IntrusiveList::<BoxTask>::new();
+		assert!(list.pop_front().is_none());
+		list.push_back(Box::new(Task::new(1)));
+		list.push_back(Box::new(Task::new(2)));
+		list.push_front(Box::new(Task::new(0)));
+		assert_eq!(list.len(), 3);
+		assert_eq!(ids(list.iter()), vec![0, 1, 2]);
+		assert_eq!(list.front().unwrap().id, 0);
+		assert_eq!(list.back().unwrap().id, 2);
+
+		let task = list.pop_back().unwrap();
+		assert_eq!(task.id, 2);
+		assert!(!task.link.is_linked());
+		assert_eq!(list.pop_front().unwrap().id, 0);
+		assert_eq!(list.pop_front().unwrap().id, 1);
+		assert!(list.is_empty());
+	}
+
+	#[test]
+	fn drop_owned() {
+		let drops = Rc::new(Cell::new(0));
+		let mut list = IntrusiveList::<BoxTask>::new();
+		for id in 0..4 {
+			list.push_back(Box::new(Task { id, _counter: Some(DropCounter(drops.clone())), ..Default::default() }));
+		}
+		drop(list.pop_front());
+		assert_eq!(drops.get(), 1);
+		drop(list);
+		assert_eq!(drops.get(), 4);
+	}
+
+	#[test]
+	fn rc_remove() {
+		let tasks: Vec<_> = (0..4).map(|id| Rc::new(Task::new(id))).collect();
+		let mut list = IntrusiveList::<RcTask>::new();
+		let mut other = IntrusiveList::<RcTask>::new();
+		for task in &tasks {
+			list.push_back(task.clone());
+		}
+		assert_eq!(Rc::strong_count(&tasks[2]), 2);
+
+		assert!(other.remove(&tasks[2]).is_none());
+		let removed = list.remove(&tasks[2]).unwrap();
+		assert!(Rc::ptr_eq(&removed, &tasks[2]));
+		assert!(list.remove(&tasks[2]).is_none());
+		assert_eq!(ids(list.iter()), vec![0, 1, 3]);
+
+		other.push_back(removed);
+		assert!(other.contains(&tasks[2]));
+		assert!(!list.contains(&tasks[2]));
+		drop(list);
+		assert_eq!(Rc::strong_count(&tasks[0]), 1);
+		assert_eq!(Rc::strong_count(&tasks[2]), 2);
+	}
+
+	#[test]
+	#[should_panic(expected = "already linked")]
+	fn double_link() {
+		let task = Rc::new(Task::new(0));
+		let mut list = IntrusiveList::<RcTask>::new();
+		list.push_back(task.clone());
+		list.push_back(task);
+	}
+
+	#[test]
+	fn borrowed_cursor() {
+		let tasks: Vec<_> = (0..5).map(Task::new).collect();
+		let extra = Task::new(5);
+		let mut list = IntrusiveList::<RefTask>::new();
+		for task in &tasks {
+			list.push_back(task);
+		}
+
+		{
+			let mut cursor = list.cursor_mut();
+			assert_eq!(cursor.get().unwrap().id, 0);
+			cursor.move_next();
+			cursor.move_next();
+			assert_eq!(cursor.remove_current().unwrap().id, 2);
+			assert_eq!(cursor.get().unwrap().id, 3);
+			cursor.insert_before(&tasks[2]);
+			cursor.move_prev();
+			cursor.move_prev();
+			assert_eq!(cursor.get().unwrap().id, 1);
+			let task = cursor.remove_current().unwrap();
+			cursor.insert_after(task);
+			assert_eq!(cursor.get().unwrap().id, 2);
+		}
+		assert_eq!(ids(list.iter()), vec![0, 2, 1, 3, 4]);
+
+		{
+			let mut cursor = list.cursor_mut();
+			cursor.move_prev();
+			assert!(cursor.get().is_none());
+			cursor.move_prev();
+			assert_eq!(cursor.get().unwrap().id, 4);
+			cursor.move_next();
+			assert!(cursor.get().is_none());
+			cursor.insert_before(&extra);
+		}
+		assert_eq!(list.back().unwrap().id, 5);
+		assert_eq!(list.len(), 6);
+	}
+}
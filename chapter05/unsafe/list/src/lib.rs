@@ -1,51 +1,180 @@
-use std::ptr;
+use std::marker::PhantomData;
+use std::ptr::NonNull;
 
+mod concurrent;
+pub use concurrent::ConcurrentQueue;
+pub mod spsc;
+mod bounded;
+pub use bounded::BoundedQueue;
+mod blocking;
+pub use blocking::BlockingQueue;
+pub mod async_queue;
+#[macro_use]
+pub mod intrusive;
+pub mod skip_map;
+pub use skip_map::SkipMap;
+pub mod xor_list;
+pub use xor_list::XorList;
+
+//head和tail都用NonNull：NonNull对T是协变的，*mut则是不变的。
+//裸指针不会自动实现Send/Sync，List独占它的所有节点，所以按Box<Node<T>>的规则手动实现。
+/// Send/Sync和协变性的检查：
+///
+/// ```
+/// use list::List;
+/// use std::thread;
+///
+/// fn assert_send_sync<T: Send + Sync>() {}
+/// assert_send_sync::<List<i32>>();
+///
+/// // 可以把队列交给工作线程
+/// let mut list = List::new();
+/// list.push(1);
+/// let handle = thread::spawn(move || list.pop());
+/// assert_eq!(handle.join().unwrap(), Some(1));
+///
+/// // 协变：List<&'static str>可以当作List<&'a str>使用
+/// fn shorten<'a>(list: List<&'static str>) -> List<&'a str> {
+///     list
+/// }
+/// let s = String::from("a");
+/// let mut list = shorten(List::new());
+/// list.push(&s);
+/// ```
+///
+/// ```compile_fail
+/// fn assert_send<T: Send>() {}
+/// assert_send::<list::List<std::rc::Rc<i32>>>();
+/// ```
+///
+/// ```compile_fail
+/// fn assert_sync<T: Sync>() {}
+/// assert_sync::<list::List<std::cell::Cell<i32>>>();
+/// ```
 pub struct List<T> {
 	head: Link<T>,
-	tail: *mut Node<T>,
+	tail: Link<T>,
+	len: usize,
+	marker: PhantomData<Box<Node<T>>>,
 }
 
-type Link<T> = Option<Box<Node<T>>>;
+type Link<T> = Option<NonNull<Node<T>>>;
 
 struct Node<T> {
 	elem: T,
 	next: Link<T>,
 }
 
+unsafe impl<T: Send> Send for List<T> {}
+unsafe impl<T: Sync> Sync for List<T> {}
+
+impl<T> Default for List<T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
 impl<T> List<T> {
 	pub fn new() -> Self {
-		List { head: None, tail: ptr::null_mut() }
+		List { head: None, tail: None, len: 0, marker: PhantomData }
+	}
+
+	fn push_node(&mut self, mut new_tail: Box<Node<T>>) {
+		new_tail.next = None;
+		let new_tail = NonNull::from(Box::leak(new_tail));
+
+		match self.tail {
+			Some(mut tail) => unsafe { tail.as_mut().next = Some(new_tail) },
+			None => self.head = Some(new_tail),
+		}
+		self.tail = Some(new_tail);
+		self.len += 1;
 	}
 
 	pub fn push(&mut self, elem: T) {
-		let mut new_tail = Box::new(Node {
+		self.push_node(Box::new(Node {
 			elem: elem,
 			next: None,
-		});
-
-		let raw_tail: *mut _ = &mut *new_tail;
-		
-		if !self.tail.is_null() {
-			unsafe {
-				(*self.tail).next = Some(new_tail);
-			}
-		} else {
-			self.head = Some(new_tail);
-		}
-		self.tail = raw_tail;
+		}));
 	}
 
-	pub fn pop(&mut self) -> Option<T> {
-		self.head.take().map(|node| {
-			let head = *node;
-			self.head = head.next;
-			
+	fn pop_node(&mut self) -> Option<Box<Node<T>>> {
+		self.head.map(|head| {
+			let mut node = unsafe { Box::from_raw(head.as_ptr()) };
+			self.head = node.next.take();
+
 			if self.head.is_none() {
-				self.tail = ptr::null_mut();
+				self.tail = None;
 			}
-			head.elem
+			self.len -= 1;
+			node
 		})
 	}
+
+	pub fn pop(&mut self) -> Option<T> {
+		self.pop_node().map(|node| node.elem)
+	}
+
+	pub fn len(&self) -> usize {
+		self.len
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len == 0
+	}
+
+	pub fn clear(&mut self) {
+		while self.pop_node().is_some() {}
+	}
+
+	//队首是下一个pop出的元素，队尾是最后push的元素
+	pub fn peek(&self) -> Option<&T> {
+		self.head.map(|node| unsafe { &(*node.as_ptr()).elem })
+	}
+
+	pub fn peek_mut(&mut self) -> Option<&mut T> {
+		self.head.map(|node| unsafe { &mut (*node.as_ptr()).elem })
+	}
+
+	pub fn peek_back(&self) -> Option<&T> {
+		self.tail.map(|node| unsafe { &(*node.as_ptr()).elem })
+	}
+
+	pub fn peek_back_mut(&mut self) -> Option<&mut T> {
+		self.tail.map(|node| unsafe { &mut (*node.as_ptr()).elem })
+	}
+
+	//把other的节点整体接到队尾，other变为空
+	pub fn append(&mut self, other: &mut Self) {
+		let other_head = match other.head.take() {
+			Some(head) => head,
+			None => return,
+		};
+		match self.tail {
+			Some(mut tail) => unsafe { tail.as_mut().next = Some(other_head) },
+			None => self.head = Some(other_head),
+		}
+		self.tail = other.tail.take();
+		self.len += other.len;
+		other.len = 0;
+	}
+}
+
+impl<T> Drop for List<T> {
+	fn drop(&mut self) {
+		self.clear();
+	}
+}
+
+//先把新元素串成一个临时队列，最后一次性接到队尾，self的tail只更新一次
+impl<T> Extend<T> for List<T> {
+	fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+		let mut batch = List::new();
+		for elem in iter {
+			batch.push(elem);
+		}
+		self.append(&mut batch);
+	}
 }
 
 //IntoIter
@@ -71,7 +200,7 @@ pub struct Iter<'a, T> {
 
 impl<T> List<T> {
 	pub fn iter(&self) -> Iter<T> {
-		Iter { next: self.head.as_deref() }
+		Iter { next: self.head.map(|node| unsafe { &*node.as_ptr() }) }
 	}
 }
 
@@ -79,7 +208,7 @@ impl<'a, T> Iterator for Iter<'a, T> {
 	type Item = &'a T;	
 	fn next(&mut self) -> Option<Self::Item> {
 		self.next.map(|node| {
-			self.next = node.next.as_deref();
+			self.next = node.next.map(|next| unsafe { &*next.as_ptr() });
 			&node.elem
 		})
 	}
@@ -92,7 +221,7 @@ pub struct IterMut<'a, T> {
 
 impl<T> List<T> {
 	pub fn iter_mut(&mut self) -> IterMut<T> {
-		IterMut { next: self.head.as_deref_mut() }
+		IterMut { next: self.head.map(|node| unsafe { &mut *node.as_ptr() }) }
 	}
 }
 
@@ -100,7 +229,7 @@ impl<'a, T> Iterator for IterMut<'a, T> {
 	type Item = &'a mut T;	
 	fn next(&mut self) -> Option<Self::Item> {
 		self.next.take().map(|node| {
-			self.next = node.next.as_deref_mut();
+			self.next = node.next.map(|next| unsafe { &mut *next.as_ptr() });
 			&mut node.elem
 		})
 	}
@@ -124,6 +253,61 @@ mod tests {
 		assert_eq!(list.pop(), None);
     }
 
+	#[test]
+	fn peek() {
+		let mut list = List::new();
+		assert_eq!(list.peek(), None);
+		assert_eq!(list.peek_back(), None);
+		list.push(1);
+		list.push(2);
+		list.push(3);
+		assert_eq!(list.len(), 3);
+		assert_eq!(list.peek(), Some(&1));
+		assert_eq!(list.peek_back(), Some(&3));
+
+		*list.peek_mut().unwrap() *= 10;
+		*list.peek_back_mut().unwrap() *= 10;
+		assert_eq!(list.pop(), Some(10));
+		assert_eq!(list.pop(), Some(2));
+		assert_eq!(list.peek(), list.peek_back());
+		assert_eq!(list.pop(), Some(30));
+		assert_eq!(list.peek_back(), None);
+		assert!(list.is_empty());
+	}
+
+	#[test]
+	fn append_extend() {
+		let mut a = List::new();
+		let mut b = List::new();
+		a.append(&mut b);
+		assert!(a.is_empty());
+
+		b.extend(vec![1, 2]);
+		a.append(&mut b);
+		assert!(b.is_empty());
+		assert_eq!(b.peek_back(), None);
+		assert_eq!(a.len(), 2);
+
+		b.extend(3..6);
+		a.append(&mut b);
+		a.extend(None);
+		a.extend(Some(6));
+		a.push(7);
+		assert_eq!(a.len(), 7);
+		assert_eq!(a.peek_back(), Some(&7));
+		assert_eq!(a.iter().copied().collect::<Vec<_>>(), vec![1, 2, 3, 4, 5, 6, 7]);
+
+		//b被清空后仍然可以继续使用
+		b.push(8);
+		assert_eq!(b.peek(), Some(&8));
+
+		a.clear();
+		assert_eq!(a.len(), 0);
+		assert_eq!(a.pop(), None);
+		a.push(9);
+		assert_eq!(a.peek(), Some(&9));
+	}
+
 	#[test]
 	fn into_iter() {
 		let mut list = List::new();
@@ -0,0 +1,456 @@
+//跳表有序映射
+//每个节点有若干层前向指针，高层指针跳过更多节点，查找时从最高层往下走，期望O(log n)。
+//每条指针还记录span：沿这条指针前进会跨过多少个第0层节点，用来做按名次查找(nth/rank_of)。
+//层数由带种子的xorshift生成，相同种子、相同操作序列得到完全相同的结构。
+use std::marker::PhantomData;
+use std::mem::MaybeUninit;
+use std::ops::{Bound, RangeBounds};
+use std::ptr;
+
+const MAX_LEVEL: usize = 16;
+const DEFAULT_SEED: u64 = 0x2545_f491_4f6c_dd1d;
+
+struct Link<K, V> {
+	next: *mut Node<K, V>,
+	span: usize,
+}
+
+struct Node<K, V> {
+	key: MaybeUninit<K>, //头节点的key/value未初始化
+	value: MaybeUninit<V>,
+	links: Vec<Link<K, V>>,
+}
+
+impl<K, V> Node<K, V> {
+	fn alloc(key: MaybeUninit<K>, value: MaybeUninit<V>, level: usize) -> *mut Self {
+		let links = (0..level).map(|_| Link { next: ptr::null_mut(), span: 0 }).collect();
+		Box::into_raw(Box::new(Node { key, value, links }))
+	}
+
+	//只能对数据节点调用
+	unsafe fn key<'a>(node: *const Self) -> &'a K {
+		(*node).key.assume_init_ref()
+	}
+}
+
+//node的第i层指针，只在insert/remove中修改时使用
+unsafe fn link<'a, K, V>(node: *mut Node<K, V>, i: usize) -> &'a mut Link<K, V> {
+	&mut (&mut (*node).links)[i]
+}
+
+//只读的版本。&self上的查找可能在多个线程里同时进行，不能产生&mut
+unsafe fn link_ref<'a, K, V>(node: *const Node<K, V>, i: usize) -> &'a Link<K, V> {
+	&(&(*node).links)[i]
+}
+
+pub struct SkipMap<K, V> {
+	head: *mut Node<K, V>,
+	level: usize, //当前使用的层数
+	len: usize,
+	rng: u64,
+	marker: PhantomData<Box<(K, V)>>,
+}
+
+unsafe impl<K: Send, V: Send> Send for SkipMap<K, V> {}
+unsafe impl<K: Sync, V: Sync> Sync for SkipMap<K, V> {}
+
+impl<K: Ord, V> Default for SkipMap<K, V> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<K: Ord, V> SkipMap<K, V> {
+	pub fn new() -> Self {
+		Self::with_seed(DEFAULT_SEED)
+	}
+
+	pub fn with_seed(seed: u64) -> Self {
+		SkipMap {
+			head: Node::alloc(MaybeUninit::uninit(), MaybeUninit::uninit(), MAX_LEVEL),
+			level: 1,
+			len: 0,
+			rng: if seed == 0 { DEFAULT_SEED } else { seed },
+			marker: PhantomData,
+		}
+	}
+
+	pub fn len(&self) -> usize {
+		self.len
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len == 0
+	}
+
+	//每升一层的概率为1/4
+	fn random_level(&mut self) -> usize {
+		let mut level = 1;
+		while level < MAX_LEVEL {
+			self.rng ^= self.rng << 13;
+			self.rng ^= self.rng >> 7;
+			self.rng ^= self.rng << 17;
+			if self.rng & 3 != 0 {
+				break;
+			}
+			level += 1;
+		}
+		level
+	}
+
+	//找到最后一个满足pred的节点（pred对有序的key必须是前缀成立），
+	//返回该节点和它的名次（从1开始，头节点为0）
+	fn find_last<F: Fn(&K) -> bool>(&self, pred: F) -> (*mut Node<K, V>, usize) {
+		let mut x = self.head;
+		let mut rank = 0;
+		unsafe {
+			for i in (0..self.level).rev() {
+				loop {
+					let forward = link_ref(x, i);
+					if forward.next.is_null() || !pred(Node::key(forward.next)) {
+						break;
+					}
+					rank += forward.span;
+					x = forward.next;
+				}
+			}
+		}
+		(x, rank)
+	}
+
+	//key对应的节点
+	fn find(&self, key: &K) -> Option<*mut Node<K, V>> {
+		let (x, _) = self.find_last(|k| k <= key);
+		if x != self.head && unsafe { Node::key(x) } == key {
+			Some(x)
+		} else {
+			None
+		}
+	}
+
+	pub fn get(&self, key: &K) -> Option<&V> {
+		self.find(key).map(|x| unsafe { (*x).value.assume_init_ref() })
+	}
+
+	pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+		self.find(key).map(|x| unsafe { (*x).value.assume_init_mut() })
+	}
+
+	pub fn contains_key(&self, key: &K) -> bool {
+		self.find(key).is_some()
+	}
+
+	//key已存在时替换value并返回旧值
+	pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+		let mut update = [self.head; MAX_LEVEL];
+		let mut rank = [0; MAX_LEVEL];
+		unsafe {
+			let mut x = self.head;
+			for i in (0..self.level).rev() {
+				rank[i] = if i + 1 == self.level { 0 } else { rank[i + 1] };
+				loop {
+					let forward = link(x, i);
+					if forward.next.is_null() || *Node::key(forward.next) >= key {
+						break;
+					}
+					rank[i] += forward.span;
+					x = forward.next;
+				}
+				update[i] = x;
+			}
+
+			let next = link(x, 0).next;
+			if !next.is_null() && *Node::key(next) == key {
+				return Some(std::mem::replace((*next).value.assume_init_mut(), value));
+			}
+
+			let level = self.random_level();
+			if level > self.level {
+				for i in self.level..level {
+					rank[i] = 0;
+					update[i] = self.head;
+					link(self.head, i).span = self.len;
+				}
+				self.level = level;
+			}
+
+			let node = Node::alloc(MaybeUninit::new(key), MaybeUninit::new(value), level);
+			for i in 0..level {
+				let prev = link(update[i], i);
+				let skipped = rank[0] - rank[i]; //update[i]到update[0]之间的节点数
+				link(node, i).next = prev.next;
+				link(node, i).span = prev.span - skipped;
+				prev.next = node;
+				prev.span = skipped + 1;
+			}
+			//更高层的指针跨过了新节点
+			for (i, &prev) in update.iter().enumerate().take(self.level).skip(level) {
+				link(prev, i).span += 1;
+			}
+		}
+		self.len += 1;
+		None
+	}
+
+	pub fn remove(&mut self, key: &K) -> Option<V> {
+		let mut update = [self.head; MAX_LEVEL];
+		unsafe {
+			let mut x = self.head;
+			for i in (0..self.level).rev() {
+				loop {
+					let forward = link(x, i);
+					if forward.next.is_null() || Node::key(forward.next) >= key {
+						break;
+					}
+					x = forward.next;
+				}
+				update[i] = x;
+			}
+
+			let node = link(x, 0).next;
+			if node.is_null() || Node::key(node) != key {
+				return None;
+			}
+			for (i, &prev) in update.iter().enumerate().take(self.level) {
+				let prev = link(prev, i);
+				if prev.next == node {
+					prev.span += link(node, i).span;
+					prev.span -= 1;
+					prev.next = link(node, i).next;
+				} else {
+					prev.span -= 1;
+				}
+			}
+			while self.level > 1 && link(self.head, self.level - 1).next.is_null() {
+				self.level -= 1;
+			}
+			self.len -= 1;
+
+			let mut node = Box::from_raw(node);
+			node.key.assume_init_drop();
+			Some(node.value.assume_init_read())
+		}
+	}
+
+	pub fn first(&self) -> Option<(&K, &V)> {
+		unsafe { entry(link_ref(self.head, 0).next) }
+	}
+
+	pub fn last(&self) -> Option<(&K, &V)> {
+		let (x, _) = self.find_last(|_| true);
+		if x == self.head {
+			None
+		} else {
+			unsafe { entry(x) }
+		}
+	}
+
+	//第index个（从0开始）元素
+	pub fn nth(&self, index: usize) -> Option<(&K, &V)> {
+		if index >= self.len {
+			return None;
+		}
+		let target = index + 1;
+		let mut x = self.head;
+		let mut rank = 0;
+		unsafe {
+			for i in (0..self.level).rev() {
+				loop {
+					let forward = link_ref(x, i);
+					if forward.next.is_null() || rank + forward.span > target {
+						break;
+					}
+					rank += forward.span;
+					x = forward.next;
+				}
+				if rank == target {
+					return entry(x);
+				}
+			}
+		}
+		None
+	}
+
+	//key的名次（从0开始）
+	pub fn rank_of(&self, key: &K) -> Option<usize> {
+		let (x, rank) = self.find_last(|k| k <= key);
+		if x != self.head && unsafe { Node::key(x) } == key {
+			Some(rank - 1)
+		} else {
+			None
+		}
+	}
+
+	pub fn iter(&self) -> Iter<'_, K, V> {
+		Iter {
+			next: unsafe { link_ref(self.head, 0).next },
+			remaining: self.len,
+			marker: PhantomData,
+		}
+	}
+
+	//按key的范围遍历，例如 map.range(&3..&7) 或 map.range(3..7)
+	pub fn range<R: RangeBounds<K>>(&self, range: R) -> Iter<'_, K, V> {
+		//start之前的最后一个节点
+		let (before, skipped) = match range.start_bound() {
+			Bound::Included(s) => self.find_last(|k| k < s),
+			Bound::Excluded(s) => self.find_last(|k| k <= s),
+			Bound::Unbounded => (self.head, 0),
+		};
+		//范围内最后一个节点的名次
+		let end = match range.end_bound() {
+			Bound::Included(e) => self.find_last(|k| k <= e).1,
+			Bound::Excluded(e) => self.find_last(|k| k < e).1,
+			Bound::Unbounded => self.len,
+		};
+		Iter {
+			next: unsafe { link_ref(before, 0).next },
+			remaining: end.saturating_sub(skipped),
+			marker: PhantomData,
+		}
+	}
+}
+
+unsafe fn entry<'a, K, V>(node: *const Node<K, V>) -> Option<(&'a K, &'a V)> {
+	if node.is_null() {
+		None
+	} else {
+		Some(((*node).key.assume_init_ref(), (*node).value.assume_init_ref()))
+	}
+}
+
+impl<K, V> Drop for SkipMap<K, V> {
+	fn drop(&mut self) {
+		unsafe {
+			let head = Box::from_raw(self.head);
+			let mut x = head.links[0].next;
+			while !x.is_null() {
+				let mut node = Box::from_raw(x);
+				x = node.links[0].next;
+				node.key.assume_init_drop();
+				node.value.assume_init_drop();
+			}
+		}
+	}
+}
+
+//Iter，同时用于iter和range，remaining为剩余的元素个数
+pub struct Iter<'a, K, V> {
+	next: *mut Node<K, V>,
+	remaining: usize,
+	marker: PhantomData<&'a (K, V)>,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+	type Item = (&'a K, &'a V);
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.remaining == 0 {
+			return None;
+		}
+		self.remaining -= 1;
+		unsafe {
+			let item = entry(self.next);
+			self.next = link_ref(self.next, 0).next;
+			item
+		}
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		(self.remaining, Some(self.remaining))
+	}
+}
+
+impl<'a, K, V> ExactSizeIterator for Iter<'a, K, V> {}
+
+#[cfg(test)]
+mod tests {
+	use super::SkipMap;
+	use std::collections::BTreeMap;
+
+	#[test]
+	fn basics() {
+		let mut map = SkipMap::new();
+		assert!(map.is_empty());
+		assert_eq!(map.first(), None);
+		assert_eq!(map.last(), None);
+		assert_eq!(map.remove(&1), None);
+
+		assert_eq!(map.insert(3, "c"), None);
+		assert_eq!(map.insert(1, "a"), None);
+		assert_eq!(map.insert(2, "b"), None);
+		assert_eq!(map.insert(2, "B"), Some("b"));
+		assert_eq!(map.len(), 3);
+
+		assert_eq!(map.get(&2), Some(&"B"));
+		assert_eq!(map.get(&4), None);
+		*map.get_mut(&3).unwrap() = "C";
+		assert_eq!(map.first(), Some((&1, &"a")));
+		assert_eq!(map.last(), Some((&3, &"C")));
+
+		assert_eq!(map.remove(&2), Some("B"));
+		assert!(!map.contains_key(&2));
+		assert_eq!(map.iter().collect::<Vec<_>>(), vec![(&1, &"a"), (&3, &"C")]);
+	}
+
+	#[test]
+	fn rank_and_range() {
+		let mut map = SkipMap::with_seed(7);
+		for i in (0..100).rev() {
+			map.insert(i * 2, i);
+		}
+		for i in 0..100 {
+			assert_eq!(map.nth(i), Some((&(i * 2), &i)));
+			assert_eq!(map.rank_of(&(i * 2)), Some(i));
+			assert_eq!(map.rank_of(&(i * 2 + 1)), None);
+		}
+		assert_eq!(map.nth(100), None);
+
+		let keys = |r: super::Iter<'_, usize, usize>| r.map(|(k, _)| *k).collect::<Vec<_>>();
+		assert_eq!(keys(map.range(10..16)), vec![10, 12, 14]);
+		assert_eq!(keys(map.range(9..=16)), vec![10, 12, 14, 16]);
+		assert_eq!(keys(map.range(195..)), vec![196, 198]);
+		assert_eq!(keys(map.range(..3)), vec![0, 2]);
+		assert_eq!(map.range(..).len(), 100);
+		assert_eq!(map.range(50..50).len(), 0);
+		assert_eq!(map.range(300..).len(), 0);
+		#[allow(clippy::reversed_empty_ranges)]
+		let reversed = map.range(20..10);
+		assert_eq!(reversed.len(), 0);
+	}
+
+	#[test]
+	fn deterministic() {
+		let build = |seed| {
+			let mut map = SkipMap::with_seed(seed);
+			for i in 0..200 {
+				map.insert(i, ());
+			}
+			map.level
+		};
+		assert_eq!(build(42), build(42));
+	}
+
+	#[test]
+	fn against_btree() {
+		let mut map = SkipMap::with_seed(1);
+		let mut expected = BTreeMap::new();
+		let mut x: u64 = 12345;
+		for _ in 0..5000 {
+			x = x.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+			let key = (x >> 33) % 500;
+			if x & 1 == 0 {
+				assert_eq!(map.insert(key, x), expected.insert(key, x));
+			} else {
+				assert_eq!(map.remove(&key), expected.remove(&key));
+			}
+		}
+		assert_eq!(map.len(), expected.len());
+		assert!(map.iter().eq(expected.iter()));
+		for (i, (k, v)) in expected.iter().enumerate() {
+			assert_eq!(map.nth(i), Some((k, v)));
+			assert_eq!(map.rank_of(k), Some(i));
+		}
+		assert!(map.range(100..300).eq(expected.range(100..300)));
+		assert_eq!(map.last(), expected.iter().next_back());
+	}
+}
@@ -0,0 +1,193 @@
+//单生产者单消费者队列
+//布局和List相同：从head出队，从tail入队，tail只有生产者访问，head只有消费者访问。
+//head指向一个哑节点，队首元素在head.next中。
+//
+//已经出队的节点不会释放，而是留在链表前部作为缓存，生产者从first开始复用它们，
+//消费者通过head_prev告诉生产者哪些节点已经可以复用。
+use std::cell::UnsafeCell;
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, Ordering};
+use std::sync::Arc;
+
+struct Node<T> {
+	elem: Option<T>,
+	next: AtomicPtr<Node<T>>,
+}
+
+impl<T> Node<T> {
+	fn alloc() -> *mut Self {
+		Box::into_raw(Box::new(Node {
+			elem: None,
+			next: AtomicPtr::new(ptr::null_mut()),
+		}))
+	}
+}
+
+struct Shared<T> {
+	//消费者
+	head: UnsafeCell<*mut Node<T>>,
+	head_prev: AtomicPtr<Node<T>>, //它之前的节点都已被消费，可以复用
+	//生产者
+	tail: UnsafeCell<*mut Node<T>>,
+	first: UnsafeCell<*mut Node<T>>, //缓存节点的起点
+	head_copy: UnsafeCell<*mut Node<T>>, //生产者最近一次读到的head_prev
+}
+
+impl<T> Drop for Shared<T> {
+	fn drop(&mut self) {
+		//从first开始，缓存节点、哑节点和未出队的节点都在同一条链上
+		let mut node = *self.first.get_mut();
+		while !node.is_null() {
+			let boxed = unsafe { Box::from_raw(node) };
+			node = boxed.next.load(Ordering::Relaxed);
+		}
+	}
+}
+
+pub struct Producer<T> {
+	shared: Arc<Shared<T>>,
+}
+
+pub struct Consumer<T> {
+	shared: Arc<Shared<T>>,
+}
+
+//两端各自只会被一个线程使用，可以分别移动到不同线程
+unsafe impl<T: Send> Send for Producer<T> {}
+unsafe impl<T: Send> Send for Consumer<T> {}
+
+pub fn channel<T>() -> (Producer<T>, Consumer<T>) {
+	let dummy = Node::alloc();
+	let shared = Arc::new(Shared {
+		head: UnsafeCell::new(dummy),
+		head_prev: AtomicPtr::new(dummy),
+		tail: UnsafeCell::new(dummy),
+		first: UnsafeCell::new(dummy),
+		head_copy: UnsafeCell::new(dummy),
+	});
+	(Producer { shared: shared.clone() }, Consumer { shared })
+}
+
+impl<T> Producer<T> {
+	//优先复用缓存节点，没有可用的才分配
+	fn alloc_node(&mut self) -> *mut Node<T> {
+		unsafe {
+			let first = self.shared.first.get();
+			let head_copy = self.shared.head_copy.get();
+			if *first == *head_copy {
+				*head_copy = self.shared.head_prev.load(Ordering::Acquire);
+			}
+			if *first != *head_copy {
+				let node = *first;
+				*first = (*node).next.load(Ordering::Relaxed);
+				(*node).next.store(ptr::null_mut(), Ordering::Relaxed);
+				return node;
+			}
+		}
+		Node::alloc()
+	}
+
+	pub fn push(&mut self, elem: T) {
+		let node = self.alloc_node();
+		unsafe {
+			(*node).elem = Some(elem);
+			let tail = self.shared.tail.get();
+			(**tail).next.store(node, Ordering::Release);
+			*tail = node;
+		}
+	}
+}
+
+impl<T> Consumer<T> {
+	pub fn pop(&mut self) -> Option<T> {
+		unsafe {
+			let head = self.shared.head.get();
+			let next = (**head).next.load(Ordering::Acquire);
+			if next.is_null() {
+				return None;
+			}
+			let elem = (*next).elem.take();
+			self.shared.head_prev.store(*head, Ordering::Release);
+			*head = next;
+			elem
+		}
+	}
+
+	pub fn is_empty(&self) -> bool {
+		unsafe { (**self.shared.head.get()).next.load(Ordering::Acquire).is_null() }
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::channel;
+	use std::sync::Arc;
+	use std::thread;
+
+	#[test]
+	fn basics() {
+		let (mut tx, mut rx) = channel();
+		assert!(rx.is_empty());
+		assert_eq!(rx.pop(), None);
+
+		tx.push(1);
+		tx.push(2);
+		assert_eq!(rx.pop(), Some(1));
+		tx.push(3);
+		assert_eq!(rx.pop(), Some(2));
+		assert_eq!(rx.pop(), Some(3));
+		assert_eq!(rx.pop(), None);
+		assert!(rx.is_empty());
+	}
+
+	#[test]
+	fn reuse_nodes() {
+		let (mut tx, mut rx) = channel();
+		let dummy = unsafe { *tx.shared.tail.get() };
+		tx.push(1);
+		assert_eq!(rx.pop(), Some(1));
+		tx.push(2);
+		assert_eq!(rx.pop(), Some(2));
+
+		//最初的哑节点已经在head_prev之前，下一次push会复用它
+		tx.push(3);
+		assert_eq!(unsafe { *tx.shared.tail.get() }, dummy);
+		assert_eq!(rx.pop(), Some(3));
+	}
+
+	#[test]
+	fn drop_remaining() {
+		let elem = Arc::new(());
+		let (mut tx, mut rx) = channel();
+		for _ in 0..10 {
+			tx.push(elem.clone());
+		}
+		for _ in 0..4 {
+			rx.pop();
+		}
+		assert_eq!(Arc::strong_count(&elem), 7);
+		drop(tx);
+		drop(rx);
+		assert_eq!(Arc::strong_count(&elem), 1);
+	}
+
+	#[test]
+	fn threads() {
+		const COUNT: usize = 100000;
+		let (mut tx, mut rx) = channel();
+		let producer = thread::spawn(move || {
+			for i in 0..COUNT {
+				tx.push(i);
+			}
+		});
+		let mut expected = 0;
+		while expected < COUNT {
+			if let Some(i) = rx.pop() {
+				assert_eq!(i, expected);
+				expected += 1;
+			}
+		}
+		producer.join().unwrap();
+		assert_eq!(rx.pop(), None);
+	}
+}
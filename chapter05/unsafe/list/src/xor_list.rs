@@ -0,0 +1,289 @@
+//异或双链表
+//每个节点只存一个字段link = prev地址 ^ next地址，从一端走过来时知道上一个节点，
+//异或一次就能得到下一个节点。这样双链表的节点和单链表一样只需要一个指针大小的链接字段。
+//首尾是对称的，所以reverse只需要交换head和tail。
+use std::marker::PhantomData;
+use std::mem;
+use std::ptr;
+
+pub struct XorList<T> {
+	head: *mut Node<T>,
+	tail: *mut Node<T>,
+	len: usize,
+	marker: PhantomData<Box<Node<T>>>,
+}
+
+struct Node<T> {
+	elem: T,
+	link: usize,
+}
+
+fn xor<T>(a: *const Node<T>, b: *const Node<T>) -> *mut Node<T> {
+	(a as usize ^ b as usize) as *mut Node<T>
+}
+
+unsafe impl<T: Send> Send for XorList<T> {}
+unsafe impl<T: Sync> Sync for XorList<T> {}
+
+impl<T> Default for XorList<T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T> XorList<T> {
+	//每个节点堆分配的字节数
+	pub const NODE_SIZE: usize = mem::size_of::<Node<T>>();
+
+	pub fn new() -> Self {
+		XorList { head: ptr::null_mut(), tail: ptr::null_mut(), len: 0, marker: PhantomData }
+	}
+
+	pub fn len(&self) -> usize {
+		self.len
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len == 0
+	}
+
+	//在end一端插入节点，head和tail的处理完全对称
+	fn push_at(end: &mut *mut Node<T>, other_end: &mut *mut Node<T>, elem: T) {
+		let node = Box::into_raw(Box::new(Node { elem, link: *end as usize }));
+		if end.is_null() {
+			*other_end = node;
+		} else {
+			//原来的端点的邻居从(null, x)变为(node, x)
+			unsafe { (**end).link ^= node as usize };
+		}
+		*end = node;
+	}
+
+	fn pop_at(end: &mut *mut Node<T>, other_end: &mut *mut Node<T>) -> Option<T> {
+		if end.is_null() {
+			return None;
+		}
+		unsafe {
+			let node = Box::from_raw(*end);
+			let next = xor(ptr::null(), node.link as *const Node<T>);
+			if next.is_null() {
+				*other_end = ptr::null_mut();
+			} else {
+				(*next).link ^= *end as usize;
+			}
+			*end = next;
+			Some(node.elem)
+		}
+	}
+
+	pub fn push_front(&mut self, elem: T) {
+		Self::push_at(&mut self.head, &mut self.tail, elem);
+		self.len += 1;
+	}
+
+	pub fn push_back(&mut self, elem: T) {
+		Self::push_at(&mut self.tail, &mut self.head, elem);
+		self.len += 1;
+	}
+
+	pub fn pop_front(&mut self) -> Option<T> {
+		let elem = Self::pop_at(&mut self.head, &mut self.tail)?;
+		self.len -= 1;
+		Some(elem)
+	}
+
+	pub fn pop_back(&mut self) -> Option<T> {
+		let elem = Self::pop_at(&mut self.tail, &mut self.head)?;
+		self.len -= 1;
+		Some(elem)
+	}
+
+	pub fn peek_front(&self) -> Option<&T> {
+		unsafe { self.head.as_ref().map(|node| &node.elem) }
+	}
+
+	pub fn peek_back(&self) -> Option<&T> {
+		unsafe { self.tail.as_ref().map(|node| &node.elem) }
+	}
+
+	pub fn reverse(&mut self) {
+		mem::swap(&mut self.head, &mut self.tail);
+	}
+
+	pub fn iter(&self) -> Iter<'_, T> {
+		Iter {
+			front: (ptr::null(), self.head),
+			back: (ptr::null(), self.tail),
+			remaining: self.len,
+			marker: PhantomData,
+		}
+	}
+}
+
+impl<T> Drop for XorList<T> {
+	fn drop(&mut self) {
+		while self.pop_front().is_some() {}
+	}
+}
+
+//IntoIter
+pub struct IntoIter<T>(XorList<T>);
+
+impl<T> IntoIterator for XorList<T> {
+	type Item = T;
+	type IntoIter = IntoIter<T>;
+	fn into_iter(self) -> IntoIter<T> {
+		IntoIter(self)
+	}
+}
+
+impl<T> Iterator for IntoIter<T> {
+	type Item = T;
+	fn next(&mut self) -> Option<Self::Item> {
+		self.0.pop_front()
+	}
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+	fn next_back(&mut self) -> Option<T> {
+		self.0.pop_back()
+	}
+}
+
+//Iter，两端各记录(上一个节点, 当前节点)
+pub struct Iter<'a, T> {
+	front: (*const Node<T>, *const Node<T>),
+	back: (*const Node<T>, *const Node<T>),
+	remaining: usize,
+	marker: PhantomData<&'a T>,
+}
+
+//从(prev, cur)前进一步，返回cur中的元素
+unsafe fn step<'a, T>(pos: &mut (*const Node<T>, *const Node<T>)) -> &'a T {
+	let (prev, cur) = *pos;
+	*pos = (cur, xor(prev, (*cur).link as *const Node<T>));
+	&(*cur).elem
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+	type Item = &'a T;
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.remaining == 0 {
+			return None;
+		}
+		self.remaining -= 1;
+		Some(unsafe { step(&mut self.front) })
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		(self.remaining, Some(self.remaining))
+	}
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+	fn next_back(&mut self) -> Option<&'a T> {
+		if self.remaining == 0 {
+			return None;
+		}
+		self.remaining -= 1;
+		Some(unsafe { step(&mut self.back) })
+	}
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+
+#[cfg(test)]
+mod tests {
+	use super::XorList;
+	use std::cell::RefCell;
+	use std::mem::size_of;
+	use std::rc::Rc;
+
+	#[test]
+	fn basics() {
+		let mut list = XorList::new();
+		assert_eq!(list.pop_front(), None);
+		assert_eq!(list.pop_back(), None);
+
+		list.push_back(2);
+		list.push_front(1);
+		list.push_back(3);
+		assert_eq!(list.len(), 3);
+		assert_eq!(list.peek_front(), Some(&1));
+		assert_eq!(list.peek_back(), Some(&3));
+
+		assert_eq!(list.pop_back(), Some(3));
+		assert_eq!(list.pop_front(), Some(1));
+		assert_eq!(list.pop_front(), Some(2));
+		assert_eq!(list.pop_back(), None);
+		assert!(list.is_empty());
+
+		list.push_front(4);
+		assert_eq!(list.pop_back(), Some(4));
+	}
+
+	#[test]
+	fn iter() {
+		let mut list = XorList::new();
+		for i in 0..5 {
+			list.push_back(i);
+		}
+		assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![0, 1, 2, 3, 4]);
+		assert_eq!(list.iter().rev().copied().collect::<Vec<_>>(), vec![4, 3, 2, 1, 0]);
+
+		let mut iter = list.iter();
+		assert_eq!(iter.next(), Some(&0));
+		assert_eq!(iter.next_back(), Some(&4));
+		assert_eq!(iter.next(), Some(&1));
+		assert_eq!(iter.next_back(), Some(&3));
+		assert_eq!(iter.len(), 1);
+		assert_eq!(iter.next(), Some(&2));
+		assert_eq!(iter.next_back(), None);
+
+		let mut into_iter = list.into_iter();
+		assert_eq!(into_iter.next_back(), Some(4));
+		assert_eq!(into_iter.next(), Some(0));
+	}
+
+	#[test]
+	fn reverse() {
+		let mut list = XorList::new();
+		for i in 0..4 {
+			list.push_back(i);
+		}
+		list.reverse();
+		assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![3, 2, 1, 0]);
+		list.push_front(10);
+		list.push_back(-1);
+		assert_eq!(list.pop_front(), Some(10));
+		list.reverse();
+		assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![-1, 0, 1, 2, 3]);
+	}
+
+	//和chapter04/basics::List的节点比较：那里每个元素是Rc<RefCell<Node>>，
+	//Rc分配里有strong/weak两个计数，RefCell有借用标记，Node里有next和prev两个指针。
+	//两个crate同名，不能互相依赖，所以RcNode照抄了那边的布局。
+	//期望值按字长和对齐计算，32位平台上同样成立。
+	#[test]
+	fn memory_usage() {
+		#[allow(dead_code)]
+		struct RcNode<T> {
+			elem: T,
+			next: Option<Rc<RefCell<RcNode<T>>>>,
+			prev: Option<Rc<RefCell<RcNode<T>>>>,
+		}
+		fn rc_node_size<T>() -> usize {
+			2 * size_of::<usize>() + size_of::<RefCell<RcNode<T>>>()
+		}
+
+		let word = size_of::<usize>();
+		let align = align_of::<u64>().max(word);
+		let round = |n: usize| n.div_ceil(align) * align;
+		//XorList：元素加一个异或后的指针
+		assert_eq!(XorList::<u64>::NODE_SIZE, round(size_of::<u64>() + word));
+		//Rc的两个计数 + RefCell的借用标记 + 元素和两个指针
+		assert_eq!(rc_node_size::<u64>(), 2 * word + round(word + round(size_of::<u64>() + 2 * word)));
+		assert!(XorList::<u8>::NODE_SIZE < rc_node_size::<u8>());
+		assert!(XorList::<[u64; 4]>::NODE_SIZE + 4 * word <= rc_node_size::<[u64; 4]>());
+	}
+}
@@ -0,0 +1,123 @@
+//cargo bench --bench two_stack
+//比较TwoStackQueue与chapter05/unsafe中带尾指针的List作为FIFO队列的耗时
+use list::TwoStackQueue;
+use std::hint::black_box;
+use std::ptr::NonNull;
+use std::time::{Duration, Instant};
+
+//chapter05/unsafe的包名也叫list，cargo不允许同名的两个包出现在一个依赖图里，
+//所以这里照抄一份它的push/pop(NonNull版本)
+struct List<T> {
+	head: Option<NonNull<Node<T>>>,
+	tail: Option<NonNull<Node<T>>>,
+}
+
+struct Node<T> {
+	elem: T,
+	next: Option<NonNull<Node<T>>>,
+}
+
+impl<T> List<T> {
+	fn new() -> Self {
+		List { head: None, tail: None }
+	}
+
+	fn push(&mut self, elem: T) {
+		let node = NonNull::from(Box::leak(Box::new(Node { elem, next: None })));
+		match self.tail {
+			Some(mut tail) => unsafe { tail.as_mut().next = Some(node) },
+			None => self.head = Some(node),
+		}
+		self.tail = Some(node);
+	}
+
+	fn pop(&mut self) -> Option<T> {
+		self.head.map(|head| {
+			let node = unsafe { Box::from_raw(head.as_ptr()) };
+			self.head = node.next;
+			if self.head.is_none() {
+				self.tail = None;
+			}
+			node.elem
+		})
+	}
+}
+
+impl<T> Drop for List<T> {
+	fn drop(&mut self) {
+		while self.pop().is_some() {}
+	}
+}
+
+const ELEMS: u64 = 1_000_000;
+const ROUNDS: usize = 5;
+
+fn time<F: FnMut()>(mut f: F) -> Duration {
+	(0..ROUNDS)
+		.map(|_| {
+			let start = Instant::now();
+			f();
+			start.elapsed()
+		})
+		.min()
+		.unwrap()
+}
+
+fn report(name: &str, best: Duration) {
+	println!(
+		"{:<28} {:>10.2?}  {:>8.2} ns/elem",
+		name,
+		best,
+		best.as_nanos() as f64 / ELEMS as f64
+	);
+}
+
+//先全部push再全部pop
+fn fill_then_drain() {
+	report("fill/drain TwoStackQueue", time(|| {
+		let mut queue = TwoStackQueue::new();
+		for i in 0..ELEMS {
+			queue.push(i);
+		}
+		while let Some(x) = queue.pop() {
+			black_box(x);
+		}
+	}));
+	report("fill/drain unsafe::List", time(|| {
+		let mut list = List::new();
+		for i in 0..ELEMS {
+			list.push(i);
+		}
+		while let Some(x) = list.pop() {
+			black_box(x);
+		}
+	}));
+}
+
+//push和pop交替进行，队列长度保持在一个小窗口内
+fn interleaved() {
+	const WINDOW: u64 = 64;
+	report("interleaved TwoStackQueue", time(|| {
+		let mut queue = TwoStackQueue::new();
+		for i in 0..ELEMS {
+			queue.push(i);
+			if i >= WINDOW {
+				black_box(queue.pop());
+			}
+		}
+	}));
+	report("interleaved unsafe::List", time(|| {
+		let mut list = List::new();
+		for i in 0..ELEMS {
+			list.push(i);
+			if i >= WINDOW {
+				black_box(list.pop());
+			}
+		}
+	}));
+}
+
+fn main() {
+	fill_then_drain();
+	interleaved();
+}
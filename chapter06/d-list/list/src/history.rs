@@ -0,0 +1,257 @@
+//基于zipper的撤销/重做历史
+//left栈顶是当前状态，往下是更早的状态；right栈里是撤销掉、还可以重做的状态。
+//undo/redo就是go_left/go_right，record会清掉right里的所有状态。
+use super::List;
+
+struct Entry<S> {
+	state: S,
+	label: Option<String>,
+}
+
+pub struct History<S> {
+	states: List<Entry<S>>,
+	max_depth: Option<usize>,
+	//left栈底已经超出max_depth、不能再撤销到的状态个数，攒够一批再一起丢弃
+	expired: usize,
+	//分组中时，后续的record直接替换当前状态，整组只占一个撤销步骤
+	grouping: bool,
+	group_started: bool,
+}
+
+impl<S> History<S> {
+	pub fn new(initial: S) -> Self {
+		let mut states = List::new();
+		states.push_left(Entry { state: initial, label: None });
+		History { states, max_depth: None, expired: 0, grouping: false, group_started: false }
+	}
+
+	//最多保留max_depth个可撤销的步骤，更早的状态不能再撤销到。
+	//它们每攒够max_depth个才一起释放，所以内存中最多有约2*max_depth个状态，record均摊O(1)
+	pub fn with_max_depth(initial: S, max_depth: usize) -> Self {
+		History { max_depth: Some(max_depth), ..Self::new(initial) }
+	}
+
+	pub fn current(&self) -> &S {
+		&self.states.peek_left().unwrap().state
+	}
+
+	pub fn undo_len(&self) -> usize {
+		self.states.position() - 1 - self.expired
+	}
+
+	pub fn redo_len(&self) -> usize {
+		self.states.len() - self.states.position()
+	}
+
+	pub fn can_undo(&self) -> bool {
+		self.undo_len() > 0
+	}
+
+	pub fn can_redo(&self) -> bool {
+		self.redo_len() > 0
+	}
+
+	pub fn record(&mut self, state: S) {
+		while self.states.pop_right().is_some() {}
+
+		if self.grouping && self.group_started {
+			self.states.peek_left_mut().unwrap().state = state;
+			return;
+		}
+		self.group_started = self.grouping;
+		self.states.push_left(Entry { state, label: None });
+
+		if let Some(max_depth) = self.max_depth {
+			if self.undo_len() > max_depth {
+				self.expired += 1;
+			}
+			if self.expired > max_depth {
+				let keep = self.states.position() - self.expired;
+				self.states.left.truncate(keep);
+				self.expired = 0;
+			}
+		}
+	}
+
+	//begin_group和end_group之间的多次record合并成一步
+	pub fn begin_group(&mut self) {
+		self.grouping = true;
+		self.group_started = false;
+	}
+
+	pub fn end_group(&mut self) {
+		self.grouping = false;
+		self.group_started = false;
+	}
+
+	pub fn undo(&mut self) -> Option<&S> {
+		if !self.can_undo() {
+			return None;
+		}
+		self.end_group();
+		self.states.go_left();
+		Some(self.current())
+	}
+
+	pub fn redo(&mut self) -> Option<&S> {
+		if !self.states.go_right() {
+			return None;
+		}
+		self.end_group();
+		Some(self.current())
+	}
+
+	//给当前状态打上标签，同名标签会被移到当前状态上
+	pub fn checkpoint(&mut self, label: &str) {
+		self.clear_label(label);
+		self.states.peek_left_mut().unwrap().label = Some(label.to_string());
+	}
+
+	fn clear_label(&mut self, label: &str) {
+		if let Some(offset) = self.find(label) {
+			self.jump(offset);
+			self.states.peek_left_mut().unwrap().label = None;
+			self.jump(-offset);
+		}
+	}
+
+	//标签相对当前状态的偏移，负数在撤销方向
+	fn find(&self, label: &str) -> Option<isize> {
+		let is_label = |entry: &Entry<S>| entry.label.as_deref() == Some(label);
+		if let Some(i) = self.states.iter_left().take(self.undo_len() + 1).position(is_label) {
+			return Some(-(i as isize));
+		}
+		self.states.iter_right().position(is_label).map(|i| i as isize + 1)
+	}
+
+	fn jump(&mut self, offset: isize) {
+		for _ in 0..offset.unsigned_abs() {
+			if offset < 0 {
+				self.states.go_left();
+			} else {
+				self.states.go_right();
+			}
+		}
+	}
+
+	//跳到标签所在的状态，中间的状态都还保留，可以继续undo/redo
+	pub fn goto_checkpoint(&mut self, label: &str) -> Option<&S> {
+		let offset = self.find(label)?;
+		self.end_group();
+		self.jump(offset);
+		Some(self.current())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::History;
+
+	#[test]
+	fn undo_redo() {
+		let mut history = History::new(0);
+		assert_eq!(history.undo(), None);
+		history.record(1);
+		history.record(2);
+		history.record(3);
+		assert_eq!(history.undo_len(), 3);
+
+		assert_eq!(history.undo(), Some(&2));
+		assert_eq!(history.undo(), Some(&1));
+		assert_eq!(history.redo(), Some(&2));
+		assert_eq!(history.redo_len(), 1);
+
+		//新的记录会丢弃可以重做的状态
+		history.record(10);
+		assert!(!history.can_redo());
+		assert_eq!(history.redo(), None);
+		assert_eq!(history.undo(), Some(&2));
+		assert_eq!(history.undo(), Some(&1));
+		assert_eq!(history.undo(), Some(&0));
+		assert_eq!(history.undo(), None);
+		assert_eq!(*history.current(), 0);
+	}
+
+	#[test]
+	fn max_depth() {
+		let mut history = History::with_max_depth(0, 2);
+		for i in 1..=5 {
+			history.record(i);
+		}
+		assert_eq!(history.undo_len(), 2);
+		assert_eq!(history.undo(), Some(&4));
+		assert_eq!(history.undo(), Some(&3));
+		assert_eq!(history.undo(), None);
+	}
+
+	#[test]
+	fn max_depth_batches() {
+		let mut history = History::with_max_depth(0, 100);
+		history.checkpoint("start");
+		for i in 1..=1000 {
+			history.record(i);
+			assert!(history.undo_len() <= 100);
+			assert!(history.states.len() <= 2 * 100 + 1);
+		}
+		assert_eq!(history.undo_len(), 100);
+		//超出深度的状态上的标签也不能再跳过去
+		assert_eq!(history.goto_checkpoint("start"), None);
+
+		for i in (900..1000).rev() {
+			assert_eq!(history.undo(), Some(&i));
+		}
+		assert_eq!(history.undo(), None);
+		assert_eq!(history.redo_len(), 100);
+
+		//撤销之后再record，丢弃的批次从新的位置算起
+		history.record(-1);
+		assert_eq!(history.undo_len(), 1);
+		for i in 0..300 {
+			history.record(i);
+		}
+		assert_eq!(history.undo_len(), 100);
+		assert_eq!(history.undo(), Some(&298));
+	}
+
+	#[test]
+	fn group() {
+		let mut history = History::new(String::new());
+		history.begin_group();
+		for s in ["h", "hi", "hi!"] {
+			history.record(s.to_string());
+		}
+		history.end_group();
+		history.record("hi!!".to_string());
+
+		assert_eq!(history.undo().map(|s| s.as_str()), Some("hi!"));
+		assert_eq!(history.undo().map(|s| s.as_str()), Some(""));
+		assert!(!history.can_undo());
+	}
+
+	#[test]
+	fn checkpoints() {
+		let mut history = History::new("a");
+		history.checkpoint("start");
+		history.record("b");
+		history.record("c");
+		history.checkpoint("saved");
+		history.record("d");
+
+		assert_eq!(history.goto_checkpoint("start"), Some(&"a"));
+		assert_eq!(history.redo_len(), 3);
+		assert_eq!(history.goto_checkpoint("saved"), Some(&"c"));
+		assert_eq!(history.redo(), Some(&"d"));
+		assert_eq!(history.goto_checkpoint("missing"), None);
+
+		//同名标签移到新位置
+		history.checkpoint("start");
+		history.undo();
+		assert_eq!(history.goto_checkpoint("start"), Some(&"d"));
+
+		//被丢弃的状态上的标签也一起消失
+		history.goto_checkpoint("saved");
+		history.record("e");
+		assert_eq!(history.goto_checkpoint("start"), None);
+		assert_eq!(history.goto_checkpoint("saved"), Some(&"c"));
+	}
+}
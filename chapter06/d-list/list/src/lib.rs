@@ -1,5 +1,15 @@
+mod text;
+pub use text::TextBuffer;
+mod history;
+pub use history::History;
+mod tree;
+pub use tree::TreeZipper;
+mod queue;
+pub use queue::TwoStackQueue;
+
 pub struct Stack<T> {
 	head: Link<T>,
+	len: usize,
 }
 
 type Link<T> = Option<Box<Node<T>>>;
@@ -11,12 +21,13 @@ struct Node<T> {
 
 impl<T> Stack<T> {
 	pub fn new() -> Self {
-		Stack { head: None } 
+		Stack { head: None, len: 0 } 
 	}
 
 	fn push_node(&mut self, mut node: Box<Node<T>>) {
 		node.next = self.head.take();
 		self.head = Some(node);
+		self.len += 1;
 	}
 
 	pub fn push(&mut self, elem: T) {
@@ -30,6 +41,7 @@ impl<T> Stack<T> {
 	fn pop_node(&mut self) -> Option<Box<Node<T>>> {
 	    self.head.take().map(|mut node| {
 	        self.head = node.next.take();
+	        self.len -= 1;
 	        node
 	    })
 	}
@@ -51,6 +63,54 @@ impl<T> Stack<T> {
 			&mut node.elem
 		})
 	}
+
+	pub fn len(&self) -> usize {
+		self.len
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len == 0
+	}
+
+	//从栈顶到栈底
+	pub fn iter(&self) -> Iter<'_, T> {
+		Iter { next: self.head.as_deref(), remaining: self.len }
+	}
+
+	pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+		IterMut { next: self.head.as_deref_mut(), remaining: self.len }
+	}
+
+	//把栈顶节点直接挪到other的栈顶，不重新分配，栈为空时返回false
+	pub fn move_top_to(&mut self, other: &mut Stack<T>) -> bool {
+		self.pop_node().map(|node| {
+			other.push_node(node);
+		}).is_some()
+	}
+
+	//挪动最多n个节点，返回实际挪动的个数。逐个挪动，所以这些元素在other中的顺序是反过来的
+	pub fn move_n_to(&mut self, other: &mut Stack<T>, n: usize) -> usize {
+		let mut moved = 0;
+		while moved < n && self.move_top_to(other) {
+			moved += 1;
+		}
+		moved
+	}
+
+	//只保留栈顶的n个元素，其余的一次性丢弃，O(n)
+	fn truncate(&mut self, n: usize) {
+		if n >= self.len {
+			return;
+		}
+		let mut link = &mut self.head;
+		for _ in 0..n {
+			link = &mut link.as_mut().unwrap().next;
+		}
+		//交给一个临时的Stack，由它的Drop逐个释放
+		let rest = Stack { head: link.take(), len: self.len - n };
+		self.len = n;
+		drop(rest);
+	}
 }
 
 impl<T> Drop for Stack<T> {
@@ -62,7 +122,84 @@ impl<T> Drop for Stack<T> {
 	}
 }
 
+//Iter
+pub struct Iter<'a, T> {
+	next: Option<&'a Node<T>>,
+	remaining: usize,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+	type Item = &'a T;
+	fn next(&mut self) -> Option<Self::Item> {
+		self.next.map(|node| {
+			self.next = node.next.as_deref();
+			self.remaining -= 1;
+			&node.elem
+		})
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		(self.remaining, Some(self.remaining))
+	}
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+
+impl<'a, T> Clone for Iter<'a, T> {
+	fn clone(&self) -> Self {
+		Iter { next: self.next, remaining: self.remaining }
+	}
+}
+
+//IterMut
+pub struct IterMut<'a, T> {
+	next: Option<&'a mut Node<T>>,
+	remaining: usize,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+	type Item = &'a mut T;
+	fn next(&mut self) -> Option<Self::Item> {
+		self.next.take().map(|node| {
+			self.next = node.next.as_deref_mut();
+			self.remaining -= 1;
+			&mut node.elem
+		})
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		(self.remaining, Some(self.remaining))
+	}
+}
+
+impl<'a, T> ExactSizeIterator for IterMut<'a, T> {}
+
+//IntoIter
+pub struct IntoIter<T>(Stack<T>);
+
+impl<T> IntoIterator for Stack<T> {
+	type Item = T;
+	type IntoIter = IntoIter<T>;
+	fn into_iter(self) -> IntoIter<T> {
+		IntoIter(self)
+	}
+}
+
+impl<T> Iterator for IntoIter<T> {
+	type Item = T;
+	fn next(&mut self) -> Option<Self::Item> {
+		self.0.pop()
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		(self.0.len, Some(self.0.len))
+	}
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
 //实现双端队列
+//left栈顶是光标左边紧挨着的元素，right栈顶是光标右边紧挨着的元素
 pub struct List<T> {
 	left: Stack<T>,
 	right: Stack<T>,
@@ -83,22 +220,166 @@ impl<T> List<T> {
 	pub fn peek_right_mut(&mut self) -> Option<&mut T> { self.right.peek_mut() }
 
 	pub fn go_left(&mut self) -> bool {
-		self.left.pop_node().map(|node| {
-			self.right.push_node(node);
-		}).is_some()
+		self.left.move_top_to(&mut self.right)
 	}
 
 	pub fn go_right(&mut self) -> bool {
-		self.right.pop_node().map(|node| {
-			self.left.push_node(node);
-		}).is_some()
+		self.right.move_top_to(&mut self.left)
+	}
+
+	//光标左边的元素个数
+	pub fn position(&self) -> usize {
+		self.left.len
+	}
+
+	pub fn len(&self) -> usize {
+		self.left.len + self.right.len
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	//把光标移到第n个元素之前，n超过len时不移动并返回false
+	pub fn seek(&mut self, n: usize) -> bool {
+		if n > self.len() {
+			return false;
+		}
+		let position = self.position();
+		if position > n {
+			self.left.move_n_to(&mut self.right, position - n);
+		} else {
+			self.right.move_n_to(&mut self.left, n - position);
+		}
+		true
+	}
+
+	pub fn go_to_start(&mut self) {
+		while self.go_left() {}
+	}
+
+	pub fn go_to_end(&mut self) {
+		while self.go_right() {}
+	}
+
+	//从光标向左，由近到远
+	pub fn iter_left(&self) -> Iter<'_, T> {
+		self.left.iter()
+	}
+
+	//从光标向右，由近到远
+	pub fn iter_right(&self) -> Iter<'_, T> {
+		self.right.iter()
+	}
+
+	//按文档顺序遍历所有元素
+	pub fn iter(&self) -> DocIter<'_, T> {
+		DocIter { left: &self.left, right: self.right.iter(), front: 0, back: self.len() }
+	}
+
+	pub fn into_vec(mut self) -> Vec<T> {
+		self.go_to_start();
+		let mut vec = Vec::with_capacity(self.len());
+		while let Some(elem) = self.pop_right() {
+			vec.push(elem);
+		}
+		vec
+	}
+}
+
+//按文档顺序的迭代器
+//左边的栈是倒序的，单链表又不能反向走，所以左边的每个元素都从栈顶数过去取，
+//整个遍历是O(n²)，但不分配内存也不需要unsafe。右边的元素向前遍历时直接沿着链表走。
+pub struct DocIter<'a, T> {
+	left: &'a Stack<T>,
+	right: Iter<'a, T>,
+	//还没有访问的范围[front, back)，都是文档中的下标
+	front: usize,
+	back: usize,
+}
+
+impl<'a, T> DocIter<'a, T> {
+	fn left_at(&self, i: usize) -> Option<&'a T> {
+		self.left.iter().nth(self.left.len - 1 - i)
 	}
 }
 
+impl<'a, T> Iterator for DocIter<'a, T> {
+	type Item = &'a T;
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.front == self.back {
+			return None;
+		}
+		self.front += 1;
+		if self.front <= self.left.len {
+			self.left_at(self.front - 1)
+		} else {
+			self.right.next()
+		}
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		let remaining = self.back - self.front;
+		(remaining, Some(remaining))
+	}
+}
+
+impl<'a, T> DoubleEndedIterator for DocIter<'a, T> {
+	fn next_back(&mut self) -> Option<Self::Item> {
+		if self.front == self.back {
+			return None;
+		}
+		self.back -= 1;
+		if self.back < self.left.len {
+			self.left_at(self.back)
+		} else {
+			//self.right已经向前走过的元素要扣掉
+			let skipped = self.front.max(self.left.len) - self.left.len;
+			self.right.clone().nth(self.back - self.left.len - skipped)
+		}
+	}
+}
+
+impl<'a, T> ExactSizeIterator for DocIter<'a, T> {}
 
 #[cfg(test)]
 mod tests {
-	use super::List;
+	use super::{List, Stack};
+
+	#[test]
+	fn stack() {
+		let mut stack = Stack::new();
+		assert!(stack.is_empty());
+		for i in 1..=3 {
+			stack.push(i);
+		}
+		assert_eq!(stack.len(), 3);
+		assert_eq!(stack.iter().collect::<Vec<_>>(), vec![&3, &2, &1]);
+		for x in stack.iter_mut() {
+			*x *= 10;
+		}
+		assert_eq!(stack.iter().len(), 3);
+		assert_eq!(stack.into_iter().collect::<Vec<_>>(), vec![30, 20, 10]);
+	}
+
+	#[test]
+	fn move_nodes() {
+		let mut a = Stack::new();
+		let mut b = Stack::new();
+		assert!(!a.move_top_to(&mut b));
+		for i in 1..=5 {
+			a.push(i);
+		}
+
+		assert!(a.move_top_to(&mut b));
+		assert_eq!(b.peek(), Some(&5));
+		assert_eq!(a.move_n_to(&mut b, 2), 2);
+		assert_eq!(b.iter().collect::<Vec<_>>(), vec![&3, &4, &5]);
+		assert_eq!(a.move_n_to(&mut b, 10), 2);
+		assert!(a.is_empty());
+		assert_eq!(b.len(), 5);
+		assert_eq!(b.into_iter().collect::<Vec<_>>(), vec![1, 2, 3, 4, 5]);
+	}
 
     #[test]
     fn walk_aboot() {
@@ -129,4 +410,78 @@ mod tests {
         assert_eq!(list.pop_left(), None);
 
     }	
+
+	#[test]
+	fn position_and_seek() {
+		let mut list = List::new();
+		for i in 0..5 {
+			list.push_left(i);                  // [0, 1, 2, 3, 4, _]
+		}
+		assert_eq!(list.len(), 5);
+		assert_eq!(list.position(), 5);
+
+		assert!(list.seek(2));                  // [0, 1, _, 2, 3, 4]
+		assert_eq!(list.position(), 2);
+		assert_eq!(list.peek_left(), Some(&1));
+		assert_eq!(list.peek_right(), Some(&2));
+		assert!(!list.seek(6));
+		assert_eq!(list.position(), 2);
+
+		list.go_to_start();
+		assert_eq!(list.position(), 0);
+		assert_eq!(list.peek_left(), None);
+		list.go_to_end();
+		assert_eq!(list.position(), 5);
+		assert_eq!(list.peek_right(), None);
+
+		list.pop_left();
+		assert_eq!(list.len(), 4);
+		assert!(!List::<i32>::new().go_left());
+		assert!(List::<i32>::new().is_empty());
+	}
+
+	#[test]
+	fn iter() {
+		let mut list = List::new();
+		list.push_left(1);
+		list.push_left(2);
+		list.push_right(4);
+		list.push_right(3);                     // [1, 2, _, 3, 4]
+
+		assert_eq!(list.iter_left().collect::<Vec<_>>(), vec![&2, &1]);
+		assert_eq!(list.iter_right().collect::<Vec<_>>(), vec![&3, &4]);
+		assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+		assert_eq!(list.iter().rev().copied().collect::<Vec<_>>(), vec![4, 3, 2, 1]);
+		assert_eq!(list.iter().len(), 4);
+
+		//从两头交替取
+		let mut iter = list.iter();
+		assert_eq!(iter.next(), Some(&1));
+		assert_eq!(iter.next_back(), Some(&4));
+		assert_eq!(iter.next(), Some(&2));
+		assert_eq!(iter.next(), Some(&3));
+		assert_eq!(iter.next_back(), None);
+		assert_eq!(iter.next(), None);
+
+		list.go_right();                        // [1, 2, 3, _, 4]
+		let mut iter = list.iter();
+		assert_eq!(iter.nth(3), Some(&4));
+		assert_eq!(iter.next_back(), None);
+		let mut iter = list.iter();
+		assert_eq!(iter.next_back(), Some(&4));
+		assert_eq!(iter.next_back(), Some(&3));
+		assert_eq!(iter.next(), Some(&1));
+		assert_eq!(iter.len(), 1);
+		assert!(list.iter().zip(list.iter().skip(1)).all(|(a, b)| a < b));
+
+		let mut empty_left = List::new();
+		empty_left.push_right(1);
+		assert_eq!(empty_left.iter().collect::<Vec<_>>(), vec![&1]);
+		let mut empty_right = List::new();
+		empty_right.push_left(1);
+		assert_eq!(empty_right.iter().rev().collect::<Vec<_>>(), vec![&1]);
+		assert_eq!(List::<i32>::new().iter().next(), None);
+
+		assert_eq!(list.into_vec(), vec![1, 2, 3, 4]);
+	}
 }
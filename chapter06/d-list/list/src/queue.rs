@@ -0,0 +1,144 @@
+//两个栈实现的FIFO队列
+//push压到inbox，pop从outbox弹出；outbox空了就把inbox的节点逐个挪过去，顺序正好反过来。
+//每个节点最多被挪动一次，所以pop均摊O(1)，而且挪动的是节点，不重新分配。
+use super::{Iter, Stack};
+
+pub struct TwoStackQueue<T> {
+	inbox: Stack<T>,
+	outbox: Stack<T>,
+}
+
+impl<T> Default for TwoStackQueue<T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T> TwoStackQueue<T> {
+	pub fn new() -> Self {
+		TwoStackQueue { inbox: Stack::new(), outbox: Stack::new() }
+	}
+
+	pub fn len(&self) -> usize {
+		self.inbox.len() + self.outbox.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	pub fn push(&mut self, elem: T) {
+		self.inbox.push(elem);
+	}
+
+	fn refill(&mut self) {
+		if self.outbox.is_empty() {
+			let n = self.inbox.len();
+			self.inbox.move_n_to(&mut self.outbox, n);
+		}
+	}
+
+	pub fn pop(&mut self) -> Option<T> {
+		self.refill();
+		self.outbox.pop()
+	}
+
+	//outbox为空时队首在inbox栈底，只能走过去，O(n)；peek_mut会顺便挪动节点
+	pub fn peek(&self) -> Option<&T> {
+		self.outbox.peek().or_else(|| self.inbox.iter().last())
+	}
+
+	pub fn peek_mut(&mut self) -> Option<&mut T> {
+		self.refill();
+		self.outbox.peek_mut()
+	}
+
+	//按FIFO顺序遍历。inbox是倒序的，先把所有节点整理到outbox里：
+	//inbox倒到临时栈上得到正序，再借inbox把outbox翻两次压到最上面。只挪节点，不分配
+	pub fn iter(&mut self) -> Iter<'_, T> {
+		if !self.inbox.is_empty() {
+			let mut ordered = Stack::new();
+			let n = self.inbox.len();
+			self.inbox.move_n_to(&mut ordered, n);
+			let n = self.outbox.len();
+			self.outbox.move_n_to(&mut self.inbox, n);
+			self.inbox.move_n_to(&mut ordered, n);
+			self.outbox = ordered;
+		}
+		self.outbox.iter()
+	}
+}
+
+//IntoIter
+pub struct IntoIter<T>(TwoStackQueue<T>);
+
+impl<T> IntoIterator for TwoStackQueue<T> {
+	type Item = T;
+	type IntoIter = IntoIter<T>;
+	fn into_iter(self) -> IntoIter<T> {
+		IntoIter(self)
+	}
+}
+
+impl<T> Iterator for IntoIter<T> {
+	type Item = T;
+	fn next(&mut self) -> Option<Self::Item> {
+		self.0.pop()
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		(self.0.len(), Some(self.0.len()))
+	}
+}
+
+impl<T> Extend<T> for TwoStackQueue<T> {
+	fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+		for elem in iter {
+			self.push(elem);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::TwoStackQueue;
+
+	#[test]
+	fn basics() {
+		let mut queue = TwoStackQueue::new();
+		assert_eq!(queue.pop(), None);
+		assert_eq!(queue.peek(), None);
+
+		queue.push(1);
+		queue.push(2);
+		assert_eq!(queue.peek(), Some(&1));
+		assert_eq!(queue.pop(), Some(1));
+		queue.push(3);
+		queue.push(4);
+		assert_eq!(queue.len(), 3);
+		assert_eq!(queue.peek(), Some(&2));
+		*queue.peek_mut().unwrap() = 20;
+		assert_eq!(queue.pop(), Some(20));
+		assert_eq!(queue.pop(), Some(3));
+		assert_eq!(queue.pop(), Some(4));
+		assert_eq!(queue.pop(), None);
+		assert!(queue.is_empty());
+	}
+
+	#[test]
+	fn iter() {
+		let mut queue = TwoStackQueue::new();
+		queue.extend(1..=3);
+		assert_eq!(queue.pop(), Some(1));
+		queue.extend(4..=6);
+
+		//outbox里是[2, 3]，inbox里是[6, 5, 4]
+		assert_eq!(queue.iter().collect::<Vec<_>>(), vec![&2, &3, &4, &5, &6]);
+		assert_eq!(queue.iter().len(), 5);
+		queue.push(7);
+		assert_eq!(queue.iter().copied().collect::<Vec<_>>(), vec![2, 3, 4, 5, 6, 7]);
+		assert_eq!(queue.pop(), Some(2));
+		queue.push(8);
+		assert_eq!(queue.into_iter().collect::<Vec<_>>(), vec![3, 4, 5, 6, 7, 8]);
+	}
+}
@@ -0,0 +1,281 @@
+//基于zipper的文本缓冲区
+//光标左边的字符在left栈里，右边的在right栈里，在光标处插入和删除都是O(1)，
+//移动光标就是把节点从一个栈挪到另一个栈，和编辑器里的gap buffer是同一个思路。
+//行号和列号(都从0开始)随着插入、删除和移动增量维护。
+use super::List;
+use std::fmt;
+
+pub struct TextBuffer {
+	text: List<char>,
+	line: usize,
+	col: usize,
+	newlines: usize,
+}
+
+impl Default for TextBuffer {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl From<&str> for TextBuffer {
+	//光标放在开头
+	fn from(s: &str) -> Self {
+		let mut buffer = TextBuffer::new();
+		buffer.insert_str(s);
+		buffer.move_to_start();
+		buffer
+	}
+}
+
+impl TextBuffer {
+	pub fn new() -> Self {
+		TextBuffer { text: List::new(), line: 0, col: 0, newlines: 0 }
+	}
+
+	//字符个数
+	pub fn len(&self) -> usize {
+		self.text.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.text.is_empty()
+	}
+
+	//光标所在的(行, 列)
+	pub fn line_col(&self) -> (usize, usize) {
+		(self.line, self.col)
+	}
+
+	//光标前的字符个数
+	pub fn offset(&self) -> usize {
+		self.text.position()
+	}
+
+	pub fn line_count(&self) -> usize {
+		self.newlines + 1
+	}
+
+	pub fn insert_str(&mut self, s: &str) {
+		for c in s.chars() {
+			self.text.push_left(c);
+			self.advance(c);
+			if c == '\n' {
+				self.newlines += 1;
+			}
+		}
+	}
+
+	//光标跨过c之后更新行列
+	fn advance(&mut self, c: char) {
+		if c == '\n' {
+			self.line += 1;
+			self.col = 0;
+		} else {
+			self.col += 1;
+		}
+	}
+
+	//光标左边的c被移走之后更新行列，跨过换行时要往回数出新的列号
+	fn retreat(&mut self, c: char) {
+		if c == '\n' {
+			self.line -= 1;
+			self.col = self.text.iter_left().take_while(|c| **c != '\n').count();
+		} else {
+			self.col -= 1;
+		}
+	}
+
+	//删除光标前最多n个字符，按原来的顺序返回被删除的文本
+	pub fn delete_backward(&mut self, n: usize) -> String {
+		let mut deleted = Vec::new();
+		while deleted.len() < n {
+			let c = match self.text.pop_left() {
+				Some(c) => c,
+				None => break,
+			};
+			self.retreat(c);
+			if c == '\n' {
+				self.newlines -= 1;
+			}
+			deleted.push(c);
+		}
+		deleted.into_iter().rev().collect()
+	}
+
+	//删除光标后最多n个字符
+	pub fn delete_forward(&mut self, n: usize) -> String {
+		let mut deleted = String::new();
+		for _ in 0..n {
+			let c = match self.text.pop_right() {
+				Some(c) => c,
+				None => break,
+			};
+			if c == '\n' {
+				self.newlines -= 1;
+			}
+			deleted.push(c);
+		}
+		deleted
+	}
+
+	fn step_left(&mut self) -> bool {
+		if !self.text.go_left() {
+			return false;
+		}
+		let c = *self.text.peek_right().unwrap();
+		self.retreat(c);
+		true
+	}
+
+	fn step_right(&mut self) -> bool {
+		if !self.text.go_right() {
+			return false;
+		}
+		let c = *self.text.peek_left().unwrap();
+		self.advance(c);
+		true
+	}
+
+	//正数向右负数向左，到头就停下，返回实际移动的距离
+	pub fn move_by(&mut self, n: isize) -> isize {
+		let mut moved = 0;
+		while moved < n && self.step_right() {
+			moved += 1;
+		}
+		while moved > n && self.step_left() {
+			moved -= 1;
+		}
+		moved
+	}
+
+	pub fn move_to_start(&mut self) {
+		while self.step_left() {}
+	}
+
+	pub fn move_to_end(&mut self) {
+		while self.step_right() {}
+	}
+
+	//移到第l行行首，行号越界时不移动并返回false
+	pub fn goto_line(&mut self, l: usize) -> bool {
+		if l >= self.line_count() {
+			return false;
+		}
+		while self.line > l || (self.line == l && self.col > 0) {
+			self.step_left();
+		}
+		while self.line < l {
+			self.step_right();
+		}
+		true
+	}
+
+	//第i行的内容，不含换行符
+	pub fn line(&self, i: usize) -> Option<String> {
+		if i >= self.line_count() {
+			return None;
+		}
+		let mut chars = Vec::new();
+		if i <= self.line {
+			let mut line = self.line;
+			for &c in self.text.iter_left() {
+				if c == '\n' {
+					if line == i {
+						break;
+					}
+					line -= 1;
+				} else if line == i {
+					chars.push(c);
+				}
+			}
+			chars.reverse();
+		}
+		if i >= self.line {
+			let mut line = self.line;
+			for &c in self.text.iter_right() {
+				if c == '\n' {
+					if line == i {
+						break;
+					}
+					line += 1;
+				} else if line == i {
+					chars.push(c);
+				}
+			}
+		}
+		Some(chars.into_iter().collect())
+	}
+}
+
+impl fmt::Display for TextBuffer {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let left: Vec<char> = self.text.iter_left().copied().collect();
+		for c in left.into_iter().rev().chain(self.text.iter_right().copied()) {
+			write!(f, "{}", c)?;
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::TextBuffer;
+
+	#[test]
+	fn edit() {
+		let mut buffer = TextBuffer::new();
+		buffer.insert_str("hello world");
+		assert_eq!(buffer.len(), 11);
+		assert_eq!(buffer.move_by(-5), -5);
+		buffer.insert_str("big ");
+		assert_eq!(buffer.to_string(), "hello big world");
+
+		assert_eq!(buffer.delete_backward(4), "big ");
+		assert_eq!(buffer.delete_forward(3), "wor");
+		assert_eq!(buffer.to_string(), "hello ld");
+		assert_eq!(buffer.delete_forward(10), "ld");
+		assert_eq!(buffer.delete_backward(100), "hello ");
+		assert!(buffer.is_empty());
+
+		buffer.insert_str("中文");
+		assert_eq!(buffer.move_by(-10), -2);
+		assert_eq!(buffer.move_by(10), 2);
+		assert_eq!(buffer.offset(), 2);
+	}
+
+	#[test]
+	fn lines() {
+		let mut buffer = TextBuffer::from("a = 1\nbb = 2\n\nccc = 3");
+		assert_eq!(buffer.line_col(), (0, 0));
+		assert_eq!(buffer.line_count(), 4);
+
+		buffer.move_to_end();
+		assert_eq!(buffer.line_col(), (3, 7));
+		assert_eq!(buffer.line(0).as_deref(), Some("a = 1"));
+		assert_eq!(buffer.line(2).as_deref(), Some(""));
+		assert_eq!(buffer.line(3).as_deref(), Some("ccc = 3"));
+		assert_eq!(buffer.line(4), None);
+
+		assert!(buffer.goto_line(1));
+		assert_eq!(buffer.line_col(), (1, 0));
+		buffer.move_by(2);
+		assert_eq!(buffer.line(1).as_deref(), Some("bb = 2"));
+		assert_eq!(buffer.line(3).as_deref(), Some("ccc = 3"));
+
+		//在行中间删除前一行的换行，两行合并
+		buffer.move_by(-2);
+		assert_eq!(buffer.delete_backward(1), "\n");
+		assert_eq!(buffer.line_col(), (0, 5));
+		assert_eq!(buffer.line_count(), 3);
+		assert_eq!(buffer.line(0).as_deref(), Some("a = 1bb = 2"));
+
+		buffer.insert_str("\n");
+		assert_eq!(buffer.line_col(), (1, 0));
+		assert!(buffer.goto_line(3));
+		assert_eq!(buffer.line_col(), (3, 0));
+		assert!(buffer.goto_line(0));
+		assert!(!buffer.goto_line(4));
+		assert_eq!(buffer.to_string(), "a = 1\nbb = 2\n\nccc = 3");
+	}
+}
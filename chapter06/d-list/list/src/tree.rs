@@ -0,0 +1,230 @@
+//多叉树(rose tree)的zipper
+//每一层的兄弟节点和List一样用两个栈保存：left是焦点左边的兄弟，right是右边的兄弟。
+//向下走时把当前这一层连同父节点的值压到parents栈里，向上走时再恢复，
+//所以上下左右移动都只是挪动节点，不复制也不重新分配。
+//不在焦点上的节点，children的光标都在最左边(全部在right栈中)。
+use super::{List, Stack};
+use std::mem;
+
+struct Tree<T> {
+	elem: T,
+	children: List<Tree<T>>,
+}
+
+impl<T> Tree<T> {
+	fn leaf(elem: T) -> Self {
+		Tree { elem, children: List::new() }
+	}
+}
+
+//父节点的值和父节点所在的那一层
+struct Crumb<T> {
+	elem: T,
+	siblings: List<Tree<T>>,
+}
+
+pub struct TreeZipper<T> {
+	focus: Tree<T>,
+	siblings: List<Tree<T>>,
+	parents: Stack<Crumb<T>>,
+}
+
+impl<T> TreeZipper<T> {
+	pub fn new(root: T) -> Self {
+		TreeZipper { focus: Tree::leaf(root), siblings: List::new(), parents: Stack::new() }
+	}
+
+	pub fn get(&self) -> &T {
+		&self.focus.elem
+	}
+
+	pub fn get_mut(&mut self) -> &mut T {
+		&mut self.focus.elem
+	}
+
+	//根节点的深度为0
+	pub fn depth(&self) -> usize {
+		self.parents.len()
+	}
+
+	pub fn is_root(&self) -> bool {
+		self.parents.is_empty()
+	}
+
+	pub fn child_count(&self) -> usize {
+		self.focus.children.len()
+	}
+
+	//焦点在兄弟中的位置
+	pub fn index(&self) -> usize {
+		self.siblings.position()
+	}
+
+	//焦点的子节点的值，从左到右
+	pub fn children(&self) -> impl Iterator<Item = &T> {
+		self.focus.children.iter_right().map(|tree| &tree.elem)
+	}
+
+	pub fn go_down(&mut self, i: usize) -> bool {
+		if i >= self.focus.children.len() {
+			return false;
+		}
+		self.focus.children.seek(i);
+		let child = self.focus.children.pop_right().unwrap();
+		let parent = mem::replace(&mut self.focus, child);
+		let siblings = mem::replace(&mut self.siblings, parent.children);
+		self.parents.push(Crumb { elem: parent.elem, siblings });
+		true
+	}
+
+	pub fn go_up(&mut self) -> bool {
+		let crumb = match self.parents.pop() {
+			Some(crumb) => crumb,
+			None => return false,
+		};
+		let mut children = mem::replace(&mut self.siblings, crumb.siblings);
+		let child = mem::replace(&mut self.focus, Tree::leaf(crumb.elem));
+		children.push_right(child);
+		children.go_to_start();
+		self.focus.children = children;
+		true
+	}
+
+	pub fn go_left(&mut self) -> bool {
+		let sibling = match self.siblings.pop_left() {
+			Some(sibling) => sibling,
+			None => return false,
+		};
+		let old = mem::replace(&mut self.focus, sibling);
+		self.siblings.push_right(old);
+		true
+	}
+
+	pub fn go_right(&mut self) -> bool {
+		let sibling = match self.siblings.pop_right() {
+			Some(sibling) => sibling,
+			None => return false,
+		};
+		let old = mem::replace(&mut self.focus, sibling);
+		self.siblings.push_left(old);
+		true
+	}
+
+	pub fn to_root(&mut self) {
+		while self.go_up() {}
+	}
+
+	//作为最后一个子节点插入，焦点不动
+	pub fn insert_child(&mut self, elem: T) {
+		let children = &mut self.focus.children;
+		children.go_to_end();
+		children.push_left(Tree::leaf(elem));
+		children.go_to_start();
+	}
+
+	//插入到焦点右边，焦点不动；根节点没有兄弟，返回false
+	pub fn insert_sibling(&mut self, elem: T) -> bool {
+		if self.is_root() {
+			return false;
+		}
+		self.siblings.push_right(Tree::leaf(elem));
+		true
+	}
+
+	//删除焦点所在的整棵子树并返回焦点的值。
+	//之后焦点依次尝试右边的兄弟、左边的兄弟和父节点；根节点不能删除
+	pub fn delete(&mut self) -> Option<T> {
+		if self.is_root() {
+			return None;
+		}
+		let next = match self.siblings.pop_right() {
+			Some(tree) => tree,
+			None => match self.siblings.pop_left() {
+				Some(tree) => tree,
+				None => {
+					let crumb = self.parents.pop().unwrap();
+					self.siblings = crumb.siblings;
+					Tree::leaf(crumb.elem)
+				}
+			},
+		};
+		Some(mem::replace(&mut self.focus, next).elem)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::TreeZipper;
+
+	fn children(zipper: &TreeZipper<&'static str>) -> Vec<&'static str> {
+		zipper.children().copied().collect()
+	}
+
+	#[test]
+	fn navigate() {
+		let mut tree = TreeZipper::new("root");
+		assert!(!tree.go_up());
+		assert!(!tree.go_left());
+		assert!(!tree.insert_sibling("x"));
+
+		tree.insert_child("a");
+		tree.insert_child("b");
+		tree.insert_child("c");
+		assert_eq!(children(&tree), vec!["a", "b", "c"]);
+		assert!(!tree.go_down(3));
+
+		assert!(tree.go_down(1));
+		assert_eq!(*tree.get(), "b");
+		assert_eq!((tree.depth(), tree.index()), (1, 1));
+		tree.insert_child("b1");
+		tree.insert_child("b2");
+		assert!(tree.go_down(0));
+		assert!(tree.go_right());
+		assert_eq!(*tree.get(), "b2");
+		assert!(!tree.go_right());
+		*tree.get_mut() = "B2";
+
+		assert!(tree.go_up());
+		assert_eq!(*tree.get(), "b");
+		assert_eq!(children(&tree), vec!["b1", "B2"]);
+		assert!(tree.go_left());
+		assert_eq!(*tree.get(), "a");
+		assert!(!tree.go_left());
+		assert!(tree.insert_sibling("a'"));
+		assert!(tree.go_right());
+		assert_eq!(*tree.get(), "a'");
+
+		tree.to_root();
+		assert!(tree.is_root());
+		assert_eq!(children(&tree), vec!["a", "a'", "b", "c"]);
+		assert!(tree.go_down(2));
+		assert_eq!(tree.child_count(), 2);
+	}
+
+	#[test]
+	fn delete() {
+		let mut tree = TreeZipper::new(0);
+		assert_eq!(tree.delete(), None);
+		for i in 1..=3 {
+			tree.insert_child(i);
+		}
+		tree.go_down(1);
+		tree.insert_child(20);
+		tree.go_down(0);
+
+		//没有兄弟时焦点回到父节点
+		assert_eq!(tree.delete(), Some(20));
+		assert_eq!(*tree.get(), 2);
+		assert_eq!(tree.child_count(), 0);
+
+		//优先移到右边的兄弟
+		assert_eq!(tree.delete(), Some(2));
+		assert_eq!(*tree.get(), 3);
+		assert_eq!(tree.delete(), Some(3));
+		assert_eq!(*tree.get(), 1);
+		assert_eq!(tree.delete(), Some(1));
+		assert_eq!(*tree.get(), 0);
+		assert!(tree.is_root());
+		assert_eq!(tree.child_count(), 0);
+	}
+}